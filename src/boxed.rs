@@ -0,0 +1,134 @@
+use std::error::Error;
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::{Sink, Stream};
+
+use crate::error::ReconnectError;
+use crate::stats::ReconnectStats;
+use crate::stream::{ConnectionStatus, ReconnectStream, UnderlyingStream};
+
+/// The operations of a [ReconnectStream] which do not depend on its underlying stream type.
+trait Erased<I, E, X>:
+    Stream<Item = I> + Sink<X, Error = ReconnectError<E>> + fmt::Debug + Send + Unpin
+{
+    fn status(&self) -> ConnectionStatus;
+    fn is_dead(&self) -> bool;
+    fn stats(&self) -> ReconnectStats;
+    fn force_reconnect(&mut self);
+}
+
+impl<T, C, I, E, X> Erased<I, E, X> for ReconnectStream<T, C, I, E>
+where
+    T: UnderlyingStream<C, I, E> + 'static,
+    T::Stream: Stream<Item = I> + Sink<X, Error = E> + Send,
+    C: Clone + Send + Sync + Unpin + 'static,
+    I: Unpin + Send + 'static,
+    E: Error + Unpin + Send + Sync + 'static,
+    X: Send + 'static,
+{
+    fn status(&self) -> ConnectionStatus {
+        ReconnectStream::status(self)
+    }
+
+    fn is_dead(&self) -> bool {
+        ReconnectStream::is_dead(self)
+    }
+
+    fn stats(&self) -> ReconnectStats {
+        ReconnectStream::stats(self)
+    }
+
+    fn force_reconnect(&mut self) {
+        ReconnectStream::force_reconnect(self)
+    }
+}
+
+/// A [ReconnectStream] with its underlying stream type erased, as returned by [ReconnectStream::boxed],
+/// which reads items of type `I` and sends items of type `X`.
+///
+/// This allows storing streams connecting to different kinds of backends in the same collection.
+/// Each poll goes through dynamic dispatch, which is negligible next to the I/O it drives.
+pub struct BoxedReconnectStream<I, E, X = I>(Box<dyn Erased<I, E, X>>);
+
+impl<T, C, I, E> ReconnectStream<T, C, I, E>
+where
+    T: UnderlyingStream<C, I, E> + 'static,
+    C: Clone + Send + Sync + Unpin + 'static,
+    I: Unpin + Send + 'static,
+    E: Error + Unpin + Send + Sync + 'static,
+{
+    /// Erases the underlying stream type, keeping the item types and the error type.
+    /// The [status](BoxedReconnectStream::status), [stats](BoxedReconnectStream::stats) and
+    /// [force_reconnect](BoxedReconnectStream::force_reconnect) remain available.
+    pub fn boxed<X>(self) -> BoxedReconnectStream<I, E, X>
+    where
+        T::Stream: Stream<Item = I> + Sink<X, Error = E> + Send,
+        X: Send + 'static,
+    {
+        BoxedReconnectStream(Box::new(self))
+    }
+}
+
+impl<I, E, X> BoxedReconnectStream<I, E, X> {
+    /// See [ReconnectStream::status].
+    pub fn status(&self) -> ConnectionStatus {
+        self.0.status()
+    }
+
+    /// See [ReconnectStream::is_dead].
+    pub fn is_dead(&self) -> bool {
+        self.0.is_dead()
+    }
+
+    /// See [ReconnectStream::stats].
+    pub fn stats(&self) -> ReconnectStats {
+        self.0.stats()
+    }
+
+    /// See [ReconnectStream::force_reconnect].
+    pub fn force_reconnect(&mut self) {
+        self.0.force_reconnect()
+    }
+}
+
+impl<I, E, X> fmt::Debug for BoxedReconnectStream<I, E, X> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BoxedReconnectStream")
+            .field(&self.0)
+            .finish()
+    }
+}
+
+impl<I, E, X> Stream for BoxedReconnectStream<I, E, X> {
+    type Item = I;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut *self.0).poll_next(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<I, E, X> Sink<X> for BoxedReconnectStream<I, E, X> {
+    type Error = ReconnectError<E>;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut *self.0).poll_ready(cx)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: X) -> Result<(), Self::Error> {
+        Pin::new(&mut *self.0).start_send(item)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut *self.0).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut *self.0).poll_close(cx)
+    }
+}
@@ -0,0 +1,100 @@
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Poll, Waker};
+
+use futures::future;
+
+/// Tells an [establish_with_cancel](crate::UnderlyingStream::establish_with_cancel) future
+/// that its connect attempt is abandoned, so the implementation can tear down what dropping
+/// the future does not, such as a registered callback or a handshake run by another task.
+///
+/// The attempt is cancelled when its future is dropped before completing, which happens upon a
+/// [connect timeout](crate::ReconnectOptions::with_connect_timeout), a shutdown, a pause,
+/// or when the [ReconnectStream](crate::ReconnectStream) itself is dropped.
+/// A token is never cancelled once its attempt completed.
+#[derive(Clone, Default)]
+pub struct CancelToken(Arc<Inner>);
+
+#[derive(Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl CancelToken {
+    /// Creates a token which is only cancelled by [cancel](Self::cancel).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the token, waking up the [cancelled](Self::cancelled) futures of all its clones.
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::SeqCst);
+        for waker in self
+            .0
+            .wakers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .drain(..)
+        {
+            waker.wake();
+        }
+    }
+
+    /// Returns true once the token is cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Completes once the token is cancelled.
+    pub fn cancelled(&self) -> impl Future<Output = ()> + Send + 'static {
+        let token = self.clone();
+        future::poll_fn(move |cx| {
+            // checked under the lock, so that a concurrent cancel is not missed
+            let mut wakers = token
+                .0
+                .wakers
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            if token.is_cancelled() {
+                return Poll::Ready(());
+            }
+            if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                wakers.push(cx.waker().clone());
+            }
+            Poll::Pending
+        })
+    }
+}
+
+impl fmt::Debug for CancelToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancelToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+/// Cancels the token of a connect attempt if it is dropped before being disarmed.
+pub(crate) struct CancelOnDrop(Option<CancelToken>);
+
+impl CancelOnDrop {
+    pub(crate) fn new(token: CancelToken) -> Self {
+        CancelOnDrop(Some(token))
+    }
+
+    /// Keeps the token from being cancelled, as the attempt completed.
+    pub(crate) fn disarm(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        if let Some(token) = self.0.take() {
+            token.cancel();
+        }
+    }
+}
@@ -0,0 +1,109 @@
+//! The clock which time is measured and slept with, so that it can be replaced for deterministic tests.
+
+use std::sync::Arc;
+#[cfg(feature = "test-util")]
+use std::sync::{Mutex, PoisonError};
+#[cfg(feature = "test-util")]
+use std::task::{Poll, Waker};
+use std::time::{Duration, Instant};
+
+use futures::future::BoxFuture;
+#[cfg(feature = "test-util")]
+use futures::{future, FutureExt};
+
+use crate::runtime;
+
+pub(crate) trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+
+    /// Time elapsed since `earlier`, or zero if it is in the future.
+    fn elapsed(&self, earlier: Instant) -> Duration {
+        self.now().saturating_duration_since(earlier)
+    }
+}
+
+pub(crate) type SharedClock = Arc<dyn Clock>;
+
+/// The clock of the runtime selected by the enabled feature.
+pub(crate) struct RealClock;
+
+impl Clock for RealClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        runtime::sleep(duration)
+    }
+}
+
+/// A clock which only moves forward when [advanced](Self::advance), to test code using
+/// [ReconnectStream](crate::ReconnectStream) without waiting for the delays to elapse,
+/// see [ReconnectStream::with_clock](crate::ReconnectStream::with_clock).
+///
+/// Clones share the same time.
+#[cfg(feature = "test-util")]
+#[derive(Debug, Clone)]
+pub struct TestClock(Arc<Mutex<TestClockState>>);
+
+#[cfg(feature = "test-util")]
+#[derive(Debug)]
+struct TestClockState {
+    now: Instant,
+    sleepers: Vec<Waker>,
+}
+
+#[cfg(feature = "test-util")]
+impl TestClock {
+    pub fn new() -> Self {
+        TestClock(Arc::new(Mutex::new(TestClockState {
+            now: Instant::now(),
+            sleepers: Vec::new(),
+        })))
+    }
+
+    /// Moves the time forward, waking up the sleeps which are over.
+    pub fn advance(&self, duration: Duration) {
+        let sleepers = {
+            let mut state = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+            state.now += duration;
+            std::mem::take(&mut state.sleepers)
+        };
+        sleepers.into_iter().for_each(Waker::wake);
+    }
+
+    /// Returns the current time of this clock.
+    pub fn now(&self) -> Instant {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner).now
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl Default for TestClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl Clock for TestClock {
+    fn now(&self) -> Instant {
+        TestClock::now(self)
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        let state = self.0.clone();
+        let deadline = TestClock::now(self) + duration;
+        future::poll_fn(move |cx| {
+            let mut state = state.lock().unwrap_or_else(PoisonError::into_inner);
+            if state.now >= deadline {
+                Poll::Ready(())
+            } else {
+                state.sleepers.push(cx.waker().clone());
+                Poll::Pending
+            }
+        })
+        .boxed()
+    }
+}
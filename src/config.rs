@@ -1,41 +1,593 @@
 //! Provides options to configure the behavior of reconnect-stream items,
 //! specifically related to reconnect behavior.
 
-use crate::strategies::ExpBackoffStrategy;
-use std::sync::Arc;
+use crate::clock::{RealClock, SharedClock};
+use crate::limiter::ReconnectLimiter;
+use crate::strategies::{DecorrelatedJitter, EqualJitter, ExpBackoffStrategy, FullJitter};
+use std::any::Any;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::future::Future;
+use std::iter;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 use std::time::Duration;
 
+use futures::future::{BoxFuture, Shared};
+use futures::FutureExt;
+use log::error;
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
+
 pub type DurationIterator = Box<dyn Iterator<Item = Duration> + Send + Sync>;
 
+/// Generates the retries of an episode, seeding their jitter from the RNG of the options.
+pub(crate) type RetriesGenerator = Arc<dyn Fn(&mut dyn RngCore) -> DurationIterator + Send + Sync>;
+
+/// Returns `None` if the error is not of the type the generator expects.
+pub(crate) type ErrorRetriesGenerator =
+    Arc<dyn Fn(&dyn Any, &mut dyn RngCore) -> Option<DurationIterator> + Send + Sync>;
+
+pub(crate) type SharedRng = Arc<Mutex<dyn RngCore + Send>>;
+
+pub(crate) type BackoffFn = Arc<dyn Fn(usize) -> Duration + Send + Sync>;
+
+pub(crate) type ConnectTimeoutFn = Arc<dyn Fn(usize) -> Duration + Send + Sync>;
+
+/// The delays of a reconnection episode.
+pub(crate) enum Retries {
+    Iter(DurationIterator),
+    /// Computed from the attempt index, which spares allocating an iterator for each episode.
+    Backoff(BackoffFn),
+}
+
+impl Retries {
+    /// Returns the delay before attempt number `attempt`, counting from zero.
+    pub(crate) fn next(&mut self, attempt: usize) -> Option<Duration> {
+        match self {
+            Retries::Iter(retries) => retries.next(),
+            Retries::Backoff(backoff_fn) => Some(backoff_fn(attempt)),
+        }
+    }
+}
+
+pub(crate) type ShouldContinue = Arc<dyn Fn(usize, Duration) -> bool + Send + Sync>;
+
+pub(crate) type ShutdownSignal = Shared<BoxFuture<'static, ()>>;
+
+pub(crate) type Callback = Arc<dyn Fn(&ReconnectContext) + Send + Sync>;
+
+pub(crate) type GiveUpCallback = Arc<dyn Fn(&ReconnectSummary<'_>) + Send + Sync>;
+
+pub(crate) type NearExhaustionCallback = Arc<dyn Fn(usize) + Send + Sync>;
+
+pub(crate) type ControlCallback = Arc<dyn Fn(&ReconnectContext) -> ControlFlow + Send + Sync>;
+
+pub(crate) type AsyncCallback =
+    Arc<dyn Fn(&ReconnectContext) -> BoxFuture<'static, ()> + Send + Sync>;
+
+pub(crate) type ErrorCallback = Arc<dyn Fn(&(dyn Error + 'static)) + Send + Sync>;
+
+pub(crate) type ItemHook = Arc<dyn Fn(&dyn Any) + Send + Sync>;
+
+/// Returns None if the item is not of the type to resend.
+pub(crate) type ItemCloner = Arc<dyn Fn(&dyn Any) -> Option<Box<dyn Any + Send>> + Send + Sync>;
+
+pub(crate) type CtorArgRefresh =
+    Arc<dyn Fn() -> BoxFuture<'static, Box<dyn Any + Send>> + Send + Sync>;
+
+fn mut_callback(cb: impl FnMut(&ReconnectContext) + 'static + Send) -> Callback {
+    let cb = Mutex::new(cb);
+    Arc::new(move |ctx| (cb.lock().unwrap_or_else(PoisonError::into_inner))(ctx))
+}
+
+/// How [ReconnectStream::connect_with_endpoints](crate::ReconnectStream::connect_with_endpoints)
+/// picks the endpoint for each connect attempt. Failed attempts always move on to the next endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndpointPolicy {
+    /// After a disconnect, start over from the first endpoint, so that the preferred one is used when available.
+    Failover,
+    /// After a disconnect, move on to the endpoint after the one that was lost, spreading connections over all of them.
+    RoundRobin,
+}
+
+/// What sending does while the first connection of a stream created without one is being established,
+/// as set by [ReconnectOptions::with_initial_connect_send_policy].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitialConnectSendPolicy {
+    /// Accept the items into the outbound buffer, up to its [capacity](ReconnectOptions::with_outbound_buffer) if set,
+    /// and send them once connected.
+    Buffer,
+    /// Fail with [Disconnected](crate::ReconnectError::Disconnected).
+    Error,
+    /// Wait in `poll_ready` and `poll_flush` until connected.
+    /// Items sent without waiting for `poll_ready` are rejected with [Disconnected](crate::ReconnectError::Disconnected).
+    Block,
+}
+
+/// What sending does when the [outbound buffer](ReconnectOptions::with_outbound_buffer) is full,
+/// as set by [ReconnectOptions::with_outbound_overflow_policy].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutboundOverflowPolicy {
+    /// Drop the oldest queued item to make room for the new one, which suits producers of fresh data such as metrics.
+    DropOldest,
+    /// Fail `start_send` with [OutboundBufferFull](crate::ReconnectError::OutboundBufferFull).
+    RejectNew,
+    /// Wait in `poll_ready` until reconnected. Items sent without waiting for `poll_ready` are rejected.
+    Block,
+}
+
+/// Information about the reconnection episode, passed to the `*_ctx` callbacks.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ReconnectContext {
+    /// Number of retries consumed from the retries iterator in the current episode.
+    pub attempt: usize,
+    /// Time elapsed since the connection was lost (or since the initial connect began).
+    pub downtime: Duration,
+    /// The delay before the next reconnect attempt, if there will be one.
+    pub next_delay: Option<Duration>,
+}
+
+/// What was tried before the stream gave up reconnecting, passed to the give up callback.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ReconnectSummary<'a> {
+    /// Number of connect attempts made in the final episode, including the retries of an unstable connection.
+    pub attempts: usize,
+    /// Time spent trying since the connection was lost (or since the initial connect began).
+    pub total_elapsed: Duration,
+    /// The error of the last failed attempt, or of the disconnect if no attempt was made, if known.
+    pub last_error: Option<&'a (dyn Error + 'static)>,
+}
+
+/// Whether to go on reconnecting, as returned by the `*_with_control` callbacks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlFlow {
+    /// Keep the connection, or reconnect as usual.
+    Continue,
+    /// Give up reconnecting: the stream becomes [dead](crate::ReconnectStream::is_dead).
+    Terminate,
+}
+
+/// A nonsensical combination of options, as reported by [ReconnectOptions::validate].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConfigError {
+    /// The retries generator yields no delay, so the stream never reconnects.
+    EmptyRetries,
+    /// The first connect grace is zero, which behaves as a grace of one:
+    /// the initial connect gives up after its first failed attempt.
+    ZeroFirstConnectGrace,
+    /// The maximum number of reconnect attempts is zero, so the stream never reconnects.
+    ZeroMaxAttempts,
+    /// The reconnect duration budget is zero, so the stream never reconnects.
+    ZeroMaxReconnectDuration,
+    /// The attempt rate limit allows no attempt at all, so it is ignored.
+    ZeroAttemptRateLimit,
+    /// The circuit breaker failure threshold is zero, so the circuit is open as soon as the connection is lost.
+    ZeroCircuitBreakerThreshold,
+    /// The connect timeout is zero, so every connect attempt times out.
+    ZeroConnectTimeout,
+    /// The idle timeout is zero, so every connection is dropped right away.
+    ZeroIdleTimeout,
+    /// The flush timeout is zero, so every connection is dropped as soon as a flush is pending.
+    ZeroFlushTimeout,
+    /// The disconnect error threshold is zero, so it is considered as a single error.
+    ZeroDisconnectErrorThreshold,
+    /// The inbound buffer has zero capacity, so no item is ever read.
+    ZeroInboundBufferCapacity,
+    /// The outbound buffer has zero capacity, so sends while disconnected always fail.
+    ZeroOutboundBufferCapacity,
+    /// The flush batch size is zero, so it is considered as a single item.
+    ZeroFlushBatchSize,
+    /// Sends fail fast while disconnected, so the outbound buffer is never used.
+    FailFastSendWithOutboundBuffer,
+    /// The min delay exceeds the max delay, so every delay is capped to the max delay.
+    MinDelayAboveMaxDelay,
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ConfigError::EmptyRetries => {
+                "retries generator yields no delay, the stream will never reconnect"
+            }
+            ConfigError::ZeroFirstConnectGrace => {
+                "first connect grace is zero, it will be considered as a single attempt"
+            }
+            ConfigError::ZeroMaxAttempts => "max attempts is zero, the stream will never reconnect",
+            ConfigError::ZeroMaxReconnectDuration => {
+                "max reconnect duration is zero, the stream will never reconnect"
+            }
+            ConfigError::ZeroAttemptRateLimit => {
+                "attempt rate limit allows no attempt, it will be ignored"
+            }
+            ConfigError::ZeroCircuitBreakerThreshold => {
+                "circuit breaker threshold is zero, the circuit will open right away"
+            }
+            ConfigError::ZeroConnectTimeout => {
+                "connect timeout is zero, every connect attempt will time out"
+            }
+            ConfigError::ZeroIdleTimeout => {
+                "idle timeout is zero, every connection will be dropped"
+            }
+            ConfigError::ZeroFlushTimeout => {
+                "flush timeout is zero, every pending flush will drop the connection"
+            }
+            ConfigError::ZeroDisconnectErrorThreshold => {
+                "disconnect error threshold is zero, it will be considered as a single error"
+            }
+            ConfigError::ZeroInboundBufferCapacity => {
+                "inbound buffer capacity is zero, no item will ever be read"
+            }
+            ConfigError::ZeroOutboundBufferCapacity => {
+                "outbound buffer capacity is zero, sends while disconnected will always fail"
+            }
+            ConfigError::ZeroFlushBatchSize => {
+                "flush batch size is zero, it will be considered as a single item"
+            }
+            ConfigError::FailFastSendWithOutboundBuffer => {
+                "sends fail fast while disconnected, the outbound buffer will never be used"
+            }
+            ConfigError::MinDelayAboveMaxDelay => {
+                "min delay exceeds the max delay, every delay will be capped to the max delay"
+            }
+        })
+    }
+}
+
+impl Error for ConfigError {}
+
 /// User specified options that control the behavior of the [ReconnectStream](crate::ReconnectStream) upon disconnect.
 #[derive(Clone)]
 pub struct ReconnectOptions(Box<Inner>);
 
 impl ReconnectOptions {
-    pub(crate) fn retries_to_attempt_fn(&self) -> &Arc<dyn Fn() -> DurationIterator + Send + Sync> {
-        &self.0.retries_to_attempt_fn
+    fn rng(&self) -> MutexGuard<'_, dyn RngCore + Send + 'static> {
+        self.0.rng.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+    /// Generates the retries from the retries generator.
+    pub(crate) fn retries(&self) -> DurationIterator {
+        (self.0.retries_to_attempt_fn)(&mut *self.rng())
+    }
+    /// Generates the retries for a reconnection episode caused by `reason`, if known.
+    pub(crate) fn retries_for<E: 'static>(&self, reason: Option<&E>) -> Retries {
+        if let (Some(reason), Some(retries_from_error_fn)) = (reason, &self.0.retries_from_error_fn)
+        {
+            match retries_from_error_fn(reason, &mut *self.rng()) {
+                Some(retries) => return Retries::Iter(retries),
+                None => error!(
+                    "The retries generator from error expects another error type than the stream."
+                ),
+            }
+        }
+        match &self.0.backoff_fn {
+            Some(backoff_fn) => Retries::Backoff(backoff_fn.clone()),
+            None => Retries::Iter(self.retries()),
+        }
+    }
+    pub(crate) fn never_reconnects(&self) -> bool {
+        self.0.never_reconnect
+    }
+    /// Generates the retries of the initial connect, which failed because of `reason`.
+    pub(crate) fn initial_retries_for<E: 'static>(&self, reason: Option<&E>) -> Retries {
+        match &self.0.initial_retries_fn {
+            Some(initial_retries_fn) => Retries::Iter(initial_retries_fn(&mut *self.rng())),
+            None => self.retries_for(reason),
+        }
     }
     pub(crate) fn exit_if_first_connect_fails(&self) -> bool {
         self.0.exit_if_first_connect_fails
     }
-    pub(crate) fn on_connect_callback(&self) -> &Arc<dyn Fn() + Send + Sync> {
+    pub(crate) fn first_connect_grace(&self) -> usize {
+        self.0.first_connect_grace
+    }
+    pub(crate) fn max_reconnect_duration(&self) -> Option<Duration> {
+        self.0.max_reconnect_duration
+    }
+    pub(crate) fn persistent_schedule(&self) -> bool {
+        self.0.persistent_schedule
+    }
+    pub(crate) fn min_healthy_duration(&self) -> Option<Duration> {
+        self.0.min_healthy_duration
+    }
+    pub(crate) fn attempt_rate_limit(&self) -> Option<(usize, Duration)> {
+        self.0.attempt_rate_limit
+    }
+    pub(crate) fn reconnect_limiter(&self) -> Option<&ReconnectLimiter> {
+        self.0.reconnect_limiter.as_deref()
+    }
+    pub(crate) fn circuit_breaker(&self) -> Option<(usize, Duration)> {
+        self.0.circuit_breaker
+    }
+    pub(crate) fn should_continue(&self) -> Option<&ShouldContinue> {
+        self.0.should_continue.as_ref()
+    }
+    pub(crate) fn min_delay(&self) -> Option<Duration> {
+        self.0.min_delay
+    }
+    pub(crate) fn max_delay(&self) -> Duration {
+        self.0.max_delay
+    }
+    pub(crate) fn max_attempts(&self) -> Option<usize> {
+        self.0.max_attempts
+    }
+    /// Samples the extra delay before the first attempt of a reconnection episode.
+    pub(crate) fn initial_jitter(&self) -> Duration {
+        match &self.0.initial_jitter {
+            Some((max, rng)) => {
+                let factor = match rng {
+                    Some(rng) => rng
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .gen::<f64>(),
+                    None => self.rng().gen::<f64>(),
+                };
+                max.mul_f64(factor)
+            }
+            None => Duration::ZERO,
+        }
+    }
+    pub(crate) fn reset_after_stable(&self) -> Option<Duration> {
+        self.0.reset_after_stable
+    }
+    pub(crate) fn shutdown_signal(&self) -> Option<&ShutdownSignal> {
+        self.0.shutdown_signal.as_ref()
+    }
+    pub(crate) fn connect_timeout(&self, attempt: usize) -> Option<Duration> {
+        match &self.0.connect_timeout_fn {
+            Some(connect_timeout_fn) => Some(connect_timeout_fn(attempt)),
+            None => self.0.connect_timeout,
+        }
+    }
+    pub(crate) fn endpoint_policy(&self) -> EndpointPolicy {
+        self.0.endpoint_policy
+    }
+    pub(crate) fn disconnect_error_threshold(&self) -> usize {
+        self.0.disconnect_error_threshold
+    }
+    pub(crate) fn idle_timeout(&self) -> Option<Duration> {
+        self.0.idle_timeout
+    }
+    pub(crate) fn flush_timeout(&self) -> Option<Duration> {
+        self.0.flush_timeout
+    }
+    pub(crate) fn inbound_buffer_capacity(&self) -> Option<usize> {
+        self.0.inbound_buffer_capacity
+    }
+    pub(crate) fn flush_batch_size(&self) -> usize {
+        self.0.flush_batch_size
+    }
+    pub(crate) fn fail_fast_send(&self) -> bool {
+        self.0.fail_fast_send
+    }
+    pub(crate) fn initial_connect_send_policy(&self) -> Option<InitialConnectSendPolicy> {
+        self.0.initial_connect_send_policy
+    }
+    pub(crate) fn outbound_buffer_capacity(&self) -> Option<usize> {
+        self.0.outbound_buffer_capacity
+    }
+    pub(crate) fn outbound_overflow_policy(&self) -> OutboundOverflowPolicy {
+        self.0.outbound_overflow_policy
+    }
+    pub(crate) fn unflushed_item_cloner(&self) -> Option<&ItemCloner> {
+        self.0.unflushed_item_cloner.as_ref()
+    }
+    pub(crate) fn on_item_received(&self) -> Option<&ItemHook> {
+        self.0.on_item_received.as_ref()
+    }
+    pub(crate) fn on_item_sent(&self) -> Option<&ItemHook> {
+        self.0.on_item_sent.as_ref()
+    }
+    pub(crate) fn ctor_arg_refresh(&self) -> Option<&CtorArgRefresh> {
+        self.0.ctor_arg_refresh.as_ref()
+    }
+    pub(crate) fn name(&self) -> Option<&str> {
+        self.0.name.as_deref()
+    }
+    pub(crate) fn on_connect_callback(&self) -> &Callback {
         &self.0.on_connect_callback
     }
-    pub(crate) fn on_disconnect_callback(&self) -> &Arc<dyn Fn() + Send + Sync> {
+    pub(crate) fn clock(&self) -> &SharedClock {
+        &self.0.clock
+    }
+    #[cfg(feature = "test-util")]
+    pub(crate) fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.0.clock = clock;
+        self
+    }
+    pub(crate) fn on_connect_async(&self) -> Option<&AsyncCallback> {
+        self.0.on_connect_async.as_ref()
+    }
+    pub(crate) fn on_disconnect_callback(&self) -> &Callback {
         &self.0.on_disconnect_callback
     }
-    pub(crate) fn on_connect_fail_callback(&self) -> &Arc<dyn Fn() + Send + Sync> {
+    pub(crate) fn on_connect_control(&self) -> Option<&ControlCallback> {
+        self.0.on_connect_control.as_ref()
+    }
+    pub(crate) fn on_disconnect_control(&self) -> Option<&ControlCallback> {
+        self.0.on_disconnect_control.as_ref()
+    }
+    pub(crate) fn on_reconnect_start_callback(&self) -> &Callback {
+        &self.0.on_reconnect_start_callback
+    }
+    pub(crate) fn on_connect_fail_callback(&self) -> &Callback {
         &self.0.on_connect_fail_callback
     }
+    pub(crate) fn on_connect_fail_err_callback(&self) -> &ErrorCallback {
+        &self.0.on_connect_fail_err_callback
+    }
+    pub(crate) fn on_give_up_callback(&self) -> &GiveUpCallback {
+        &self.0.on_give_up_callback
+    }
+    pub(crate) fn on_near_exhaustion(&self) -> Option<&(usize, NearExhaustionCallback)> {
+        self.0.on_near_exhaustion.as_ref()
+    }
+}
+
+impl fmt::Debug for ReconnectOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        fn placeholder<T>(value: &Option<T>, text: &'static str) -> Option<&'static str> {
+            value.as_ref().map(|_| text)
+        }
+
+        let inner = &self.0;
+        f.debug_struct("ReconnectOptions")
+            .field("retries_to_attempt_fn", &"<fn>")
+            .field("backoff_fn", &placeholder(&inner.backoff_fn, "<fn>"))
+            .field(
+                "retries_from_error_fn",
+                &placeholder(&inner.retries_from_error_fn, "<fn>"),
+            )
+            .field(
+                "initial_retries_fn",
+                &placeholder(&inner.initial_retries_fn, "<fn>"),
+            )
+            .field("never_reconnect", &inner.never_reconnect)
+            .field(
+                "exit_if_first_connect_fails",
+                &inner.exit_if_first_connect_fails,
+            )
+            .field("first_connect_grace", &inner.first_connect_grace)
+            .field("max_reconnect_duration", &inner.max_reconnect_duration)
+            .field("max_attempts", &inner.max_attempts)
+            .field("min_delay", &inner.min_delay)
+            .field("max_delay", &inner.max_delay)
+            .field(
+                "should_continue",
+                &placeholder(&inner.should_continue, "<fn>"),
+            )
+            .field("reset_after_stable", &inner.reset_after_stable)
+            .field("min_healthy_duration", &inner.min_healthy_duration)
+            .field("persistent_schedule", &inner.persistent_schedule)
+            .field("attempt_rate_limit", &inner.attempt_rate_limit)
+            .field("reconnect_limiter", &inner.reconnect_limiter)
+            .field("circuit_breaker", &inner.circuit_breaker)
+            .field(
+                "initial_jitter",
+                &inner.initial_jitter.as_ref().map(|(max, _)| max),
+            )
+            .field("rng", &"<rng>")
+            .field(
+                "shutdown_signal",
+                &placeholder(&inner.shutdown_signal, "<future>"),
+            )
+            .field("connect_timeout", &inner.connect_timeout)
+            .field(
+                "connect_timeout_fn",
+                &placeholder(&inner.connect_timeout_fn, "<fn>"),
+            )
+            .field("endpoint_policy", &inner.endpoint_policy)
+            .field("idle_timeout", &inner.idle_timeout)
+            .field("flush_timeout", &inner.flush_timeout)
+            .field(
+                "disconnect_error_threshold",
+                &inner.disconnect_error_threshold,
+            )
+            .field("inbound_buffer_capacity", &inner.inbound_buffer_capacity)
+            .field("outbound_buffer_capacity", &inner.outbound_buffer_capacity)
+            .field("outbound_overflow_policy", &inner.outbound_overflow_policy)
+            .field("flush_batch_size", &inner.flush_batch_size)
+            .field("fail_fast_send", &inner.fail_fast_send)
+            .field(
+                "initial_connect_send_policy",
+                &inner.initial_connect_send_policy,
+            )
+            .field(
+                "unflushed_item_cloner",
+                &placeholder(&inner.unflushed_item_cloner, "<fn>"),
+            )
+            .field(
+                "on_item_received",
+                &placeholder(&inner.on_item_received, "<fn>"),
+            )
+            .field("on_item_sent", &placeholder(&inner.on_item_sent, "<fn>"))
+            .field("name", &inner.name)
+            .field(
+                "ctor_arg_refresh",
+                &placeholder(&inner.ctor_arg_refresh, "<fn>"),
+            )
+            .field("on_connect_callback", &"<callback>")
+            .field(
+                "on_connect_async",
+                &placeholder(&inner.on_connect_async, "<callback>"),
+            )
+            .field("on_disconnect_callback", &"<callback>")
+            .field(
+                "on_connect_control",
+                &placeholder(&inner.on_connect_control, "<callback>"),
+            )
+            .field(
+                "on_disconnect_control",
+                &placeholder(&inner.on_disconnect_control, "<callback>"),
+            )
+            .field("on_reconnect_start_callback", &"<callback>")
+            .field("on_connect_fail_callback", &"<callback>")
+            .field("on_connect_fail_err_callback", &"<callback>")
+            .field("on_give_up_callback", &"<callback>")
+            .field(
+                "on_near_exhaustion",
+                &inner
+                    .on_near_exhaustion
+                    .as_ref()
+                    .map(|(threshold, _)| threshold),
+            )
+            .finish()
+    }
 }
 
 #[derive(Clone)]
 struct Inner {
-    retries_to_attempt_fn: Arc<dyn Fn() -> DurationIterator + Send + Sync>,
+    retries_to_attempt_fn: RetriesGenerator,
+    backoff_fn: Option<BackoffFn>,
+    retries_from_error_fn: Option<ErrorRetriesGenerator>,
+    initial_retries_fn: Option<RetriesGenerator>,
+    never_reconnect: bool,
     exit_if_first_connect_fails: bool,
-    on_connect_callback: Arc<dyn Fn() + Send + Sync>,
-    on_disconnect_callback: Arc<dyn Fn() + Send + Sync>,
-    on_connect_fail_callback: Arc<dyn Fn() + Send + Sync>,
+    first_connect_grace: usize,
+    max_reconnect_duration: Option<Duration>,
+    max_attempts: Option<usize>,
+    min_delay: Option<Duration>,
+    max_delay: Duration,
+    should_continue: Option<ShouldContinue>,
+    reset_after_stable: Option<Duration>,
+    min_healthy_duration: Option<Duration>,
+    persistent_schedule: bool,
+    attempt_rate_limit: Option<(usize, Duration)>,
+    reconnect_limiter: Option<Arc<ReconnectLimiter>>,
+    circuit_breaker: Option<(usize, Duration)>,
+    /// The max initial jitter, and its own RNG if it was seeded separately
+    initial_jitter: Option<(Duration, Option<Arc<Mutex<StdRng>>>)>,
+    rng: SharedRng,
+    shutdown_signal: Option<ShutdownSignal>,
+    connect_timeout: Option<Duration>,
+    connect_timeout_fn: Option<ConnectTimeoutFn>,
+    endpoint_policy: EndpointPolicy,
+    idle_timeout: Option<Duration>,
+    flush_timeout: Option<Duration>,
+    disconnect_error_threshold: usize,
+    inbound_buffer_capacity: Option<usize>,
+    outbound_buffer_capacity: Option<usize>,
+    outbound_overflow_policy: OutboundOverflowPolicy,
+    flush_batch_size: usize,
+    fail_fast_send: bool,
+    initial_connect_send_policy: Option<InitialConnectSendPolicy>,
+    unflushed_item_cloner: Option<ItemCloner>,
+    on_item_received: Option<ItemHook>,
+    on_item_sent: Option<ItemHook>,
+    name: Option<String>,
+    ctor_arg_refresh: Option<CtorArgRefresh>,
+    on_connect_callback: Callback,
+    on_connect_async: Option<AsyncCallback>,
+    clock: SharedClock,
+    on_disconnect_callback: Callback,
+    on_connect_control: Option<ControlCallback>,
+    on_disconnect_control: Option<ControlCallback>,
+    on_reconnect_start_callback: Callback,
+    on_connect_fail_callback: Callback,
+    on_connect_fail_err_callback: ErrorCallback,
+    on_give_up_callback: GiveUpCallback,
+    on_near_exhaustion: Option<(usize, NearExhaustionCallback)>,
 }
 
 impl ReconnectOptions {
@@ -45,14 +597,172 @@ impl ReconnectOptions {
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         ReconnectOptions(Box::new(Inner {
-            retries_to_attempt_fn: Arc::new(|| Box::new(ExpBackoffStrategy::default().into_iter())),
+            retries_to_attempt_fn: Arc::new(|rng: &mut dyn RngCore| {
+                Box::new(
+                    ExpBackoffStrategy::default()
+                        .with_seed(rng.next_u64())
+                        .into_iter(),
+                )
+            }),
+            backoff_fn: None,
+            retries_from_error_fn: None,
+            initial_retries_fn: None,
+            never_reconnect: false,
             exit_if_first_connect_fails: true,
-            on_connect_callback: Arc::new(|| {}),
-            on_disconnect_callback: Arc::new(|| {}),
-            on_connect_fail_callback: Arc::new(|| {}),
+            first_connect_grace: 1,
+            max_reconnect_duration: None,
+            max_attempts: None,
+            min_delay: None,
+            max_delay: Duration::from_secs(24 * 60 * 60),
+            should_continue: None,
+            reset_after_stable: None,
+            min_healthy_duration: None,
+            persistent_schedule: false,
+            attempt_rate_limit: None,
+            reconnect_limiter: None,
+            circuit_breaker: None,
+            initial_jitter: None,
+            rng: Arc::new(Mutex::new(StdRng::from_entropy())),
+            shutdown_signal: None,
+            connect_timeout: None,
+            connect_timeout_fn: None,
+            endpoint_policy: EndpointPolicy::Failover,
+            idle_timeout: None,
+            flush_timeout: None,
+            disconnect_error_threshold: 1,
+            inbound_buffer_capacity: None,
+            outbound_buffer_capacity: None,
+            outbound_overflow_policy: OutboundOverflowPolicy::RejectNew,
+            flush_batch_size: 1,
+            fail_fast_send: false,
+            initial_connect_send_policy: None,
+            unflushed_item_cloner: None,
+            on_item_received: None,
+            on_item_sent: None,
+            name: None,
+            ctor_arg_refresh: None,
+            on_connect_callback: Arc::new(|_| {}),
+            on_connect_async: None,
+            clock: Arc::new(RealClock),
+            on_disconnect_callback: Arc::new(|_| {}),
+            on_connect_control: None,
+            on_disconnect_control: None,
+            on_reconnect_start_callback: Arc::new(|_| {}),
+            on_connect_fail_callback: Arc::new(|_| {}),
+            on_connect_fail_err_callback: Arc::new(|_| {}),
+            on_give_up_callback: Arc::new(|_| {}),
+            on_near_exhaustion: None,
         }))
     }
 
+    /// Connects once, and gives up on the first disconnect without reconnecting,
+    /// which gives a baseline behaving like the bare underlying stream.
+    ///
+    /// The stream becomes [dead](crate::ReconnectStream::is_dead) right after the disconnect callback is invoked,
+    /// and no retries generator is ever invoked, not even a generator configured later on.
+    /// The initial connect fails right away too, unless
+    /// [with_exit_if_first_connect_fails](Self::with_exit_if_first_connect_fails) is set to false,
+    /// in which case the retries generator is used for it.
+    pub fn never_reconnect() -> Self {
+        let mut options = ReconnectOptions::new().with_retries_generator(Vec::new);
+        options.0.never_reconnect = true;
+        options
+    }
+
+    /// Returns the first `n` delays between reconnect attempts, as generated by the retries generator
+    /// and bounded by the [min](Self::with_min_delay) and [max](Self::with_max_delay) delays,
+    /// without running a stream. Fewer delays are returned if the retries run out before.
+    ///
+    /// The initial jitter and the attempt rate limit are not applied, and jittered delays are drawn
+    /// from a throwaway RNG, so that peeking does not shift the schedules of the streams.
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use stream_reconnect::ReconnectOptions;
+    ///
+    /// let options = ReconnectOptions::new()
+    ///     .with_exponential_backoff(Duration::from_secs(5), 2.0, Duration::from_secs(60));
+    /// assert_eq!(options.peek_schedule(3)[2], Duration::from_secs(20));
+    /// ```
+    pub fn peek_schedule(&self, n: usize) -> Vec<Duration> {
+        let mut retries = match &self.0.backoff_fn {
+            Some(backoff_fn) => Retries::Backoff(backoff_fn.clone()),
+            None => Retries::Iter((self.0.retries_to_attempt_fn)(&mut StdRng::from_entropy())),
+        };
+        (0..n)
+            .map_while(|attempt| retries.next(attempt))
+            .map(|delay| match self.0.min_delay {
+                Some(min) => delay.max(min),
+                None => delay,
+            })
+            .map(|delay| delay.min(self.0.max_delay))
+            .collect()
+    }
+
+    /// Checks the options for nonsensical combinations, such as a retries generator yielding no delay.
+    ///
+    /// The retries generator is invoked once with a throwaway random number generator, and no delay is drawn from it,
+    /// so this does not disturb a [seeded](Self::with_rng) sequence nor consume delays from a
+    /// [channel](crate::strategies::from_receiver). Only a generator whose size hint tells it is empty,
+    /// such as an empty `Vec`, is reported.
+    ///
+    /// The [ReconnectStream](crate::ReconnectStream) does not validate the options, this is up to the caller.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !self.0.never_reconnect
+            && self.0.backoff_fn.is_none()
+            && (self.0.retries_to_attempt_fn)(&mut StdRng::from_entropy())
+                .size_hint()
+                .1
+                == Some(0)
+        {
+            return Err(ConfigError::EmptyRetries);
+        }
+        if self.0.first_connect_grace == 0 {
+            return Err(ConfigError::ZeroFirstConnectGrace);
+        }
+        if self.0.max_attempts == Some(0) {
+            return Err(ConfigError::ZeroMaxAttempts);
+        }
+        if self.0.max_reconnect_duration == Some(Duration::ZERO) {
+            return Err(ConfigError::ZeroMaxReconnectDuration);
+        }
+        if let Some((0, _)) = self.0.attempt_rate_limit {
+            return Err(ConfigError::ZeroAttemptRateLimit);
+        }
+        if let Some((0, _)) = self.0.circuit_breaker {
+            return Err(ConfigError::ZeroCircuitBreakerThreshold);
+        }
+        if self.0.connect_timeout == Some(Duration::ZERO) {
+            return Err(ConfigError::ZeroConnectTimeout);
+        }
+        if self.0.idle_timeout == Some(Duration::ZERO) {
+            return Err(ConfigError::ZeroIdleTimeout);
+        }
+        if self.0.flush_timeout == Some(Duration::ZERO) {
+            return Err(ConfigError::ZeroFlushTimeout);
+        }
+        if self.0.disconnect_error_threshold == 0 {
+            return Err(ConfigError::ZeroDisconnectErrorThreshold);
+        }
+        if self.0.inbound_buffer_capacity == Some(0) {
+            return Err(ConfigError::ZeroInboundBufferCapacity);
+        }
+        if self.0.outbound_buffer_capacity == Some(0) {
+            return Err(ConfigError::ZeroOutboundBufferCapacity);
+        }
+        if self.0.flush_batch_size == 0 {
+            return Err(ConfigError::ZeroFlushBatchSize);
+        }
+        if self.0.fail_fast_send && self.0.outbound_buffer_capacity.is_some() {
+            return Err(ConfigError::FailFastSendWithOutboundBuffer);
+        }
+        if matches!(self.0.min_delay, Some(min) if min > self.0.max_delay) {
+            return Err(ConfigError::MinDelayAboveMaxDelay);
+        }
+        Ok(())
+    }
+
     /// Represents a function that generates an Iterator
     /// to schedule the wait between reconnection attempts.
     /// This method allows the user to provide any function that returns a value
@@ -80,7 +790,215 @@ impl ReconnectOptions {
         I: 'static + Send + Sync + Iterator<Item = Duration>,
         IN: IntoIterator<IntoIter = I, Item = Duration>,
     {
-        self.0.retries_to_attempt_fn = Arc::new(move || Box::new(retries_generator().into_iter()));
+        self.0.retries_to_attempt_fn =
+            Arc::new(move |_: &mut dyn RngCore| Box::new(retries_generator().into_iter()));
+        self.0.backoff_fn = None;
+        self
+    }
+
+    /// Computes the delay before each reconnection attempt from its index, starting from zero,
+    /// instead of pulling it from an iterator. Unlike [with_retries_generator](Self::with_retries_generator),
+    /// this allocates nothing for each reconnection episode, which matters with many flapping streams.
+    ///
+    /// Retries never stop by themselves, so [with_max_attempts](Self::with_max_attempts)
+    /// or [with_max_reconnect_duration](Self::with_max_reconnect_duration) can bound them.
+    /// The jitter options turn it back into an iterator, and allocate again.
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use stream_reconnect::ReconnectOptions;
+    ///
+    /// // Wait 100ms, 200ms, 400ms, ... but never longer than 10 seconds.
+    /// let options = ReconnectOptions::new().with_backoff_fn(|attempt| {
+    ///     Duration::from_millis(100)
+    ///         .saturating_mul(1 << attempt.min(16))
+    ///         .min(Duration::from_secs(10))
+    /// });
+    /// ```
+    pub fn with_backoff_fn(
+        mut self,
+        backoff_fn: impl Fn(usize) -> Duration + Send + Sync + 'static,
+    ) -> Self {
+        self.0.backoff_fn = Some(Arc::new(backoff_fn));
+        self
+    }
+
+    /// Replaces the backoff fn, if any, with an equivalent retries generator.
+    fn backoff_fn_into_generator(&mut self) {
+        if let Some(backoff_fn) = self.0.backoff_fn.take() {
+            self.0.retries_to_attempt_fn = Arc::new(move |_: &mut dyn RngCore| {
+                let backoff_fn = backoff_fn.clone();
+                Box::new((0..).map(move |attempt| backoff_fn(attempt)))
+            });
+        }
+    }
+
+    /// Like [with_retries_generator](Self::with_retries_generator), but the generator receives the error
+    /// which caused the disconnect, so that the backoff can depend on it
+    /// (ex: a long backoff when rate limited, a short one for a transient network failure).
+    ///
+    /// The error is known for write errors, for the initial connect failure, and for read items if
+    /// [UnderlyingStream::read_disconnect_error](crate::UnderlyingStream::read_disconnect_error) is implemented.
+    /// When the disconnect has no error (ex: the stream ended), the generator set by
+    /// [with_retries_generator](Self::with_retries_generator) is used instead.
+    ///
+    /// `E` must be the error type of the [ReconnectStream](crate::ReconnectStream),
+    /// otherwise an error is logged and the other generators are used instead.
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use std::time::Duration;
+    /// use stream_reconnect::ReconnectOptions;
+    ///
+    /// let options = ReconnectOptions::new().with_retries_generator_from_error(|err: &io::Error| {
+    ///     if err.kind() == io::ErrorKind::ConnectionRefused {
+    ///         vec![Duration::from_secs(30); 10]
+    ///     } else {
+    ///         vec![Duration::from_millis(100); 10]
+    ///     }
+    /// });
+    /// ```
+    pub fn with_retries_generator_from_error<E, F, I, IN>(mut self, retries_generator: F) -> Self
+    where
+        E: 'static,
+        F: 'static + Send + Sync + Fn(&E) -> IN,
+        I: 'static + Send + Sync + Iterator<Item = Duration>,
+        IN: IntoIterator<IntoIter = I, Item = Duration>,
+    {
+        self.0.retries_from_error_fn = Some(Arc::new(move |err: &dyn Any, _: &mut dyn RngCore| {
+            let retries = retries_generator(err.downcast_ref::<E>()?).into_iter();
+            Some(Box::new(retries) as DurationIterator)
+        }));
+        self
+    }
+
+    /// Use a separate retries generator for the initial connect, which is only retried if
+    /// [with_exit_if_first_connect_fails](Self::with_exit_if_first_connect_fails) is set to false.
+    /// This allows retrying aggressively on startup, and more gently once connected.
+    /// After the first connection, the generator set by [with_retries_generator](Self::with_retries_generator) takes over.
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use stream_reconnect::ReconnectOptions;
+    ///
+    /// let options = ReconnectOptions::new()
+    ///     .with_exit_if_first_connect_fails(false)
+    ///     .with_initial_retries_generator(|| vec![Duration::from_millis(100); 5])
+    ///     .with_exponential_backoff(Duration::from_secs(1), 2.0, Duration::from_secs(60));
+    /// ```
+    pub fn with_initial_retries_generator<F, I, IN>(mut self, retries_generator: F) -> Self
+    where
+        F: 'static + Send + Sync + Fn() -> IN,
+        I: 'static + Send + Sync + Iterator<Item = Duration>,
+        IN: IntoIterator<IntoIter = I, Item = Duration>,
+    {
+        self.0.initial_retries_fn = Some(Arc::new(move |_: &mut dyn RngCore| {
+            Box::new(retries_generator().into_iter())
+        }));
+        self
+    }
+
+    /// Use an exponential backoff between reconnection attempts, starting from `base` and multiplied
+    /// by `factor` after each attempt. Individual delays are clamped to `max`, so retries never stop.
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use stream_reconnect::ReconnectOptions;
+    ///
+    /// // Wait 1s, 2s, 4s, ... between attempts, but never longer than 1 minute,
+    /// // each delay randomized with full jitter.
+    /// let options = ReconnectOptions::new()
+    ///     .with_exponential_backoff(Duration::from_secs(1), 2.0, Duration::from_secs(60))
+    ///     .with_full_jitter();
+    /// ```
+    pub fn with_exponential_backoff(self, base: Duration, factor: f64, max: Duration) -> Self {
+        self.with_retries_generator(move || {
+            ExpBackoffStrategy::new(base, factor, 0.0).with_max(max)
+        })
+    }
+
+    /// Wait the same `interval` before each reconnection attempt, giving up after `max_attempts`
+    /// attempts, or retrying forever if it is `None`.
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use stream_reconnect::ReconnectOptions;
+    ///
+    /// // Retry every 2 seconds, up to 5 times.
+    /// let options = ReconnectOptions::new().with_fixed_interval(Duration::from_secs(2), Some(5));
+    /// ```
+    pub fn with_fixed_interval(self, interval: Duration, max_attempts: Option<usize>) -> Self {
+        let attempts = max_attempts.unwrap_or(usize::MAX);
+        self.with_retries_generator(move || iter::repeat_n(interval, attempts))
+    }
+
+    /// Use the ["decorrelated jitter"](crate::strategies::DecorrelatedJitter) backoff
+    /// between reconnection attempts, which behaves better than plain exponential backoff
+    /// when many clients reconnect at once.
+    pub fn with_decorrelated_jitter(mut self, base: Duration, cap: Duration) -> Self {
+        self.0.retries_to_attempt_fn = Arc::new(move |rng: &mut dyn RngCore| {
+            Box::new(DecorrelatedJitter::new(base, cap).with_seed(rng.next_u64()))
+        });
+        self.0.backoff_fn = None;
+        self
+    }
+
+    /// Randomize each delay produced by the current retries generator with
+    /// [full jitter](crate::strategies::FullJitter).
+    /// Should be called after the retries generator is configured.
+    pub fn with_full_jitter(mut self) -> Self {
+        self.backoff_fn_into_generator();
+        let retries_to_attempt_fn = self.0.retries_to_attempt_fn.clone();
+        self.0.retries_to_attempt_fn = Arc::new(move |rng: &mut dyn RngCore| {
+            let seed = rng.next_u64();
+            Box::new(FullJitter::new(retries_to_attempt_fn(rng)).with_seed(seed))
+        });
+        if let Some(retries_from_error_fn) = self.0.retries_from_error_fn.take() {
+            self.0.retries_from_error_fn = Some(Arc::new(move |err, rng: &mut dyn RngCore| {
+                let seed = rng.next_u64();
+                retries_from_error_fn(err, rng).map(|retries| {
+                    Box::new(FullJitter::new(retries).with_seed(seed)) as DurationIterator
+                })
+            }));
+        }
+        if let Some(initial_retries_fn) = self.0.initial_retries_fn.take() {
+            self.0.initial_retries_fn = Some(Arc::new(move |rng: &mut dyn RngCore| {
+                let seed = rng.next_u64();
+                Box::new(FullJitter::new(initial_retries_fn(rng)).with_seed(seed))
+            }));
+        }
+        self
+    }
+
+    /// Randomize each delay produced by the current retries generator with
+    /// [equal jitter](crate::strategies::EqualJitter).
+    /// Should be called after the retries generator is configured.
+    pub fn with_equal_jitter(mut self) -> Self {
+        self.backoff_fn_into_generator();
+        let retries_to_attempt_fn = self.0.retries_to_attempt_fn.clone();
+        self.0.retries_to_attempt_fn = Arc::new(move |rng: &mut dyn RngCore| {
+            let seed = rng.next_u64();
+            Box::new(EqualJitter::new(retries_to_attempt_fn(rng)).with_seed(seed))
+        });
+        if let Some(retries_from_error_fn) = self.0.retries_from_error_fn.take() {
+            self.0.retries_from_error_fn = Some(Arc::new(move |err, rng: &mut dyn RngCore| {
+                let seed = rng.next_u64();
+                retries_from_error_fn(err, rng).map(|retries| {
+                    Box::new(EqualJitter::new(retries).with_seed(seed)) as DurationIterator
+                })
+            }));
+        }
+        if let Some(initial_retries_fn) = self.0.initial_retries_fn.take() {
+            self.0.initial_retries_fn = Some(Arc::new(move |rng: &mut dyn RngCore| {
+                let seed = rng.next_u64();
+                Box::new(EqualJitter::new(initial_retries_fn(rng)).with_seed(seed))
+            }));
+        }
         self
     }
 
@@ -91,21 +1009,905 @@ impl ReconnectOptions {
         self
     }
 
-    /// Invoked when the [ReconnectStream](crate::ReconnectStream) establishes a connection
-    pub fn with_on_connect_callback(mut self, cb: impl Fn() + 'static + Send + Sync) -> Self {
-        self.0.on_connect_callback = Arc::new(cb);
+    /// Lets the initial connect fail `attempts` times in a row before
+    /// [with_exit_if_first_connect_fails](Self::with_exit_if_first_connect_fails) gives up,
+    /// which tolerates a transient failure at startup while still bailing on a misconfigured endpoint.
+    /// The attempts in between are delayed by the initial retries. Defaults to 1, meaning only the literal first attempt.
+    /// A grace of 0 is treated as 1, since the first attempt is always made.
+    ///
+    /// This has no effect when [with_exit_if_first_connect_fails](Self::with_exit_if_first_connect_fails) is set to false.
+    pub fn with_first_connect_grace(mut self, attempts: usize) -> Self {
+        self.0.first_connect_grace = attempts;
+        self
+    }
+
+    /// Give up reconnecting once the time spent since the connection was lost would exceed `max`,
+    /// regardless of how many delays the retries iterator still has.
+    /// An attempt whose delay would end past the budget is not scheduled.
+    /// Once the budget is exhausted, the stream terminates just like when the retries are exhausted.
+    pub fn with_max_reconnect_duration(mut self, max: Duration) -> Self {
+        self.0.max_reconnect_duration = Some(max);
+        self
+    }
+
+    /// Give up after `max` reconnect attempts, even if the retries iterator is not exhausted yet.
+    /// The count starts over after each successful connection,
+    /// unless it is carried over by [with_reset_after_stable](Self::with_reset_after_stable).
+    pub fn with_max_attempts(mut self, max: usize) -> Self {
+        self.0.max_attempts = Some(max);
+        self
+    }
+
+    /// Never wait less than `min` before a reconnect attempt, whichever retries are used.
+    /// Every delay pulled from the retries is raised to at least `min`, after any jitter is applied,
+    /// which protects a rate-limited endpoint without baking the floor into each generator.
+    pub fn with_min_delay(mut self, min: Duration) -> Self {
+        self.0.min_delay = Some(min);
+        self
+    }
+
+    /// Never wait more than `max` before a reconnect attempt, whichever retries are used.
+    /// Every delay pulled from the retries is capped to `max`, after the [min delay](Self::with_min_delay)
+    /// and any jitter are applied, so that a generator yielding a huge delay such as [Duration::MAX]
+    /// cannot overflow the scheduling of the attempt. Defaults to 24 hours.
+    pub fn with_max_delay(mut self, max: Duration) -> Self {
+        self.0.max_delay = max;
+        self
+    }
+
+    /// Consult `predicate` before scheduling each reconnect attempt, with the number of attempts made so far
+    /// in the episode and the time elapsed since the connection was lost, and give up once it returns false.
+    /// This allows stopping on a condition of the application, and composes with the other limits.
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::Arc;
+    /// use stream_reconnect::ReconnectOptions;
+    ///
+    /// let session_valid = Arc::new(AtomicBool::new(true));
+    /// let options = ReconnectOptions::new().with_should_continue(move |_attempt, _elapsed| {
+    ///     session_valid.load(Ordering::Relaxed)
+    /// });
+    /// ```
+    pub fn with_should_continue(
+        mut self,
+        predicate: impl Fn(usize, Duration) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.0.should_continue = Some(Arc::new(predicate));
+        self
+    }
+
+    /// Only start over with a fresh retries iterator once a reconnected connection has stayed up for `period`.
+    /// If it drops again sooner, the backoff continues where it left off instead of restarting from the first delay,
+    /// which keeps a flapping link from reconnecting in rapid succession.
+    ///
+    /// By default, each reconnection episode starts with a fresh iterator.
+    pub fn with_reset_after_stable(mut self, period: Duration) -> Self {
+        self.0.reset_after_stable = Some(period);
+        self
+    }
+
+    /// Count a connection which drops within `duration` as a failed attempt, instead of a success
+    /// which resets the backoff, which keeps an endpoint accepting connections only to drop them
+    /// right away from making the stream reconnect in a tight loop.
+    /// The backoff continues where it left off as with [with_reset_after_stable](Self::with_reset_after_stable),
+    /// and the connection counts towards the [failed attempts](crate::ReconnectStats::failed_attempts)
+    /// and the failures of the [circuit breaker](Self::with_circuit_breaker).
+    pub fn with_min_healthy_duration(mut self, duration: Duration) -> Self {
+        self.0.min_healthy_duration = Some(duration);
         self
     }
 
+    /// Keep a single retries iterator for the whole life of the stream instead of one per reconnection episode,
+    /// so that the backoff keeps going forward across disconnects, and a finite iterator
+    /// is a retry budget for the lifetime of the stream.
+    ///
+    /// The retries generator is called upon the first disconnect, and only again when the options are
+    /// [replaced](crate::ReconnectStream::set_options). The initial connect still has its own retries.
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use stream_reconnect::ReconnectOptions;
+    ///
+    /// // Reconnect at most 100 times over the life of the stream.
+    /// let options = ReconnectOptions::new()
+    ///     .with_fixed_interval(Duration::from_secs(1), Some(100))
+    ///     .with_persistent_schedule(true);
+    /// ```
+    pub fn with_persistent_schedule(mut self, enabled: bool) -> Self {
+        self.0.persistent_schedule = enabled;
+        self
+    }
+
+    /// Allow at most `max` connect attempts within any period of `per`, the initial connect included.
+    /// When the limit is reached, the next attempt is postponed until the oldest attempt leaves the window,
+    /// even if the retries iterator asks for a shorter delay.
+    /// Attempts made on demand, such as a [migration](crate::ReconnectStream::migrate), are counted and postponed too.
+    ///
+    /// This protects a fragile server from a retries iterator that would retry too often,
+    /// and composes with the backoff instead of replacing it.
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use stream_reconnect::ReconnectOptions;
+    ///
+    /// // No more than 10 attempts per minute, however short the backoff is.
+    /// let options = ReconnectOptions::new()
+    ///     .with_retries_generator(|| std::iter::repeat(Duration::from_secs(1)))
+    ///     .with_attempt_rate_limit(10, Duration::from_secs(60));
+    /// ```
+    pub fn with_attempt_rate_limit(mut self, max: usize, per: Duration) -> Self {
+        self.0.attempt_rate_limit = Some((max, per));
+        self
+    }
+
+    /// Share a reconnect budget with the other streams given the same `limiter`,
+    /// so that their reconnect attempts taken together do not exceed its rate.
+    /// When the budget is exhausted, the stream waits for it on top of the backoff delay.
+    /// See [ReconnectLimiter] for an example.
+    pub fn with_reconnect_limiter(mut self, limiter: Arc<ReconnectLimiter>) -> Self {
+        self.0.reconnect_limiter = Some(limiter);
+        self
+    }
+
+    /// Open the circuit after `failure_threshold` consecutive failed reconnect attempts.
+    /// While the circuit is open, sends are rejected with [CircuitOpen](crate::ReconnectError::CircuitOpen)
+    /// instead of waiting for the connection, and a single probe attempt is made after each `cooldown`.
+    /// A successful probe closes the circuit, while a failed one keeps it open for another cooldown.
+    ///
+    /// Once the circuit is open, the retries iterator and [with_max_attempts](Self::with_max_attempts)
+    /// no longer bound the reconnection, but [with_max_reconnect_duration](Self::with_max_reconnect_duration) still does.
+    pub fn with_circuit_breaker(mut self, failure_threshold: usize, cooldown: Duration) -> Self {
+        self.0.circuit_breaker = Some((failure_threshold, cooldown));
+        self
+    }
+
+    /// Add a random delay between zero and `max` to the first attempt of each reconnection episode,
+    /// including the first retry of the initial connect.
+    /// Unlike [with_full_jitter](Self::with_full_jitter), this only shifts the start of the schedule,
+    /// which keeps a fleet of clients losing their connection at once from reconnecting in lockstep.
+    /// The jitter is drawn from the [RNG](Self::with_rng) of the options.
+    pub fn with_initial_jitter(mut self, max: Duration) -> Self {
+        self.0.initial_jitter = Some((max, None));
+        self
+    }
+
+    /// Like [with_initial_jitter](Self::with_initial_jitter), but with a separate seeded RNG for reproducible delays.
+    pub fn with_initial_jitter_seeded(mut self, max: Duration, seed: u64) -> Self {
+        let rng = StdRng::seed_from_u64(seed);
+        self.0.initial_jitter = Some((max, Some(Arc::new(Mutex::new(rng)))));
+        self
+    }
+
+    /// Draw the randomness of all the jitter options from `rng`, which gives reproducible schedules
+    /// across a whole stream, or desynchronizes a fleet of streams by seeding each one differently.
+    /// The jitter of each reconnection episode is seeded from `rng`, as is the [initial jitter](Self::with_initial_jitter).
+    /// Defaults to an RNG seeded from the system entropy.
+    ///
+    /// The RNG is shared by the clones of the options.
+    /// Jitter strategies built within a custom retries generator keep their own RNG.
+    pub fn with_rng(mut self, rng: impl RngCore + Send + 'static) -> Self {
+        self.0.rng = Arc::new(Mutex::new(rng));
+        self
+    }
+
+    /// Like [with_rng](Self::with_rng), with an RNG seeded from `seed`.
+    pub fn with_rng_seed(self, seed: u64) -> Self {
+        self.with_rng(StdRng::seed_from_u64(seed))
+    }
+
+    /// Stop reconnecting for good once `signal` completes, typically on application shutdown.
+    /// A backoff delay or connect attempt in progress is cancelled, the stream then ends
+    /// and the sink fails with [Shutdown](crate::ReconnectError::Shutdown).
+    /// A live connection is not affected until it is lost.
+    ///
+    /// The initial connect also gives up when the signal completes while waiting to retry.
+    pub fn with_shutdown_signal<F>(mut self, signal: F) -> Self
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.0.shutdown_signal = Some(signal.boxed().shared());
+        self
+    }
+
+    /// Give up a connect attempt if `establish` does not complete within `timeout`,
+    /// which keeps a handshake that never completes from stalling the reconnection.
+    /// A timed out attempt counts as a failed one: the connect fail callbacks are invoked
+    /// with [ConnectTimeout](crate::ReconnectError::ConnectTimeout) as the error, and the next delay applies.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.0.connect_timeout = Some(timeout);
+        self.0.connect_timeout_fn = None;
+        self
+    }
+
+    /// Like [with_connect_timeout](Self::with_connect_timeout), but computes the timeout of each connect attempt
+    /// from its number, the [attempt](ReconnectContext::attempt) given to the callbacks,
+    /// so that a degraded server is given more time as the attempts go on.
+    /// The initial connect attempt is number zero, and the attempts of a reconnection episode start from one.
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use stream_reconnect::ReconnectOptions;
+    ///
+    /// // Wait 5 seconds for the first attempts, up to 30 seconds for the later ones.
+    /// let options = ReconnectOptions::new().with_connect_timeout_fn(|attempt| {
+    ///     Duration::from_secs(5 * (attempt as u64 + 1)).min(Duration::from_secs(30))
+    /// });
+    /// ```
+    pub fn with_connect_timeout_fn(
+        mut self,
+        timeout_fn: impl Fn(usize) -> Duration + Send + Sync + 'static,
+    ) -> Self {
+        self.0.connect_timeout_fn = Some(Arc::new(timeout_fn));
+        self.0.connect_timeout = None;
+        self
+    }
+
+    /// Choose how the endpoints given to
+    /// [ReconnectStream::connect_with_endpoints](crate::ReconnectStream::connect_with_endpoints) are tried.
+    /// Defaults to [EndpointPolicy::Failover].
+    pub fn with_endpoint_policy(mut self, policy: EndpointPolicy) -> Self {
+        self.0.endpoint_policy = policy;
+        self
+    }
+
+    /// Treat the connection as lost if no item is read within `timeout`, which catches
+    /// half-open connections that never report an error.
+    /// The timer restarts on every item read and only runs while connected and being polled for items.
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.0.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Treat the connection as lost if a flush of the underlying sink does not complete within `timeout`,
+    /// which catches a half-open write path that never drains.
+    /// The timer starts when a flush is first pending, and stops once it completes.
+    pub fn with_flush_timeout(mut self, timeout: Duration) -> Self {
+        self.0.flush_timeout = Some(timeout);
+        self
+    }
+
+    /// Only reconnect once `threshold` read items in a row are considered a disconnect by
+    /// [is_read_disconnect_error](crate::UnderlyingStream::is_read_disconnect_error),
+    /// which spares reconnecting upon a spurious error of a noisy but usable link.
+    /// The tolerated items are discarded, and any other item read in between resets the count.
+    /// The end of the underlying stream is always considered a disconnect.
+    /// Defaults to 1, meaning every such item triggers a reconnect.
+    pub fn with_disconnect_error_threshold(mut self, threshold: usize) -> Self {
+        self.0.disconnect_error_threshold = threshold;
+        self
+    }
+
+    /// Read ahead up to `capacity` items from the underlying stream, which caps the memory used
+    /// to absorb a burst of items, such as the backlog delivered right after reconnecting.
+    /// Once the buffer is full, the underlying stream is not polled until items are consumed,
+    /// so that backpressure propagates to it.
+    /// Buffered items are still yielded after the connection is lost.
+    pub fn with_inbound_buffer(mut self, capacity: usize) -> Self {
+        self.0.inbound_buffer_capacity = Some(capacity);
+        self
+    }
+
+    /// Queue up to `capacity` outbound items while disconnected instead of stalling `poll_ready`.
+    /// Queued items are sent on the new connection before any other item once reconnected,
+    /// one at a time, and are flushed by [batches](Self::with_flush_batch_size), so that the backpressure of
+    /// the underlying sink is respected.
+    /// When the queue is full, `start_send` fails with
+    /// [OutboundBufferFull](crate::ReconnectError::OutboundBufferFull),
+    /// unless another [overflow policy](Self::with_outbound_overflow_policy) is chosen.
+    ///
+    /// Note that `poll_flush` still waits until the queued items are sent on a live connection.
+    pub fn with_outbound_buffer(mut self, capacity: usize) -> Self {
+        self.0.outbound_buffer_capacity = Some(capacity);
+        self
+    }
+
+    /// Choose what sending does when the [outbound buffer](Self::with_outbound_buffer) is full.
+    /// Defaults to [OutboundOverflowPolicy::RejectNew].
+    /// The items dropped or rejected are counted in [dropped_outbound](crate::ReconnectStats::dropped_outbound).
+    pub fn with_outbound_overflow_policy(mut self, policy: OutboundOverflowPolicy) -> Self {
+        self.0.outbound_overflow_policy = policy;
+        self
+    }
+
+    /// Flush the items of the [outbound buffer](Self::with_outbound_buffer) once every `size` items
+    /// when sending them after a reconnect, instead of flushing each of them, which saves syscalls for chatty protocols.
+    /// The last batch is flushed even if it is not full. Defaults to 1, i.e. each item is flushed before the next one is sent.
+    pub fn with_flush_batch_size(mut self, size: usize) -> Self {
+        self.0.flush_batch_size = size;
+        self
+    }
+
+    /// If enabled, sending while disconnected fails right away with
+    /// [Disconnected](crate::ReconnectError::Disconnected) instead of waiting for the stream to reconnect,
+    /// so that latency-sensitive callers can shed load instead of queueing it.
+    /// This applies to `poll_ready`, `start_send` and `poll_flush`, and the stream keeps reconnecting meanwhile.
+    ///
+    /// This is the opposite of [with_outbound_buffer](Self::with_outbound_buffer), and the two are mutually exclusive.
+    pub fn with_fail_fast_send(mut self, enabled: bool) -> Self {
+        self.0.fail_fast_send = enabled;
+        self
+    }
+
+    /// Choose what sending does until the first connection is established, for a stream created without one
+    /// such as by [reconfigure_clone](crate::ReconnectStream::reconfigure_clone), which callers may start sending to
+    /// before the handshake completes. The policy applies to `poll_ready`, `start_send` and `poll_flush`.
+    ///
+    /// By default, sending during the initial connect behaves as while reconnecting, following
+    /// [with_outbound_buffer](Self::with_outbound_buffer) and [with_fail_fast_send](Self::with_fail_fast_send).
+    pub fn with_initial_connect_send_policy(mut self, policy: InitialConnectSendPolicy) -> Self {
+        self.0.initial_connect_send_policy = Some(policy);
+        self
+    }
+
+    /// If enabled, keep a copy of each outbound item of type `I` until the underlying sink confirms it is flushed,
+    /// and send the copies again on the new connection after a reconnect, before any buffered item.
+    ///
+    /// Otherwise, items accepted by `start_send` but not flushed when the connection is lost are dropped
+    /// along with the old connection, and a `poll_flush` in progress resolves once the new connection is flushed.
+    ///
+    /// Items of another type, sent through another `Sink` impl of the underlying stream, are not copied
+    /// and are dropped along with the old connection as if this option was disabled.
+    /// This is the only option which requires the items to be `Clone`, and clones each of them when enabled.
+    /// Note that items may be delivered twice if the old connection did send them before reporting the error.
+    /// # Examples
+    ///
+    /// ```
+    /// use stream_reconnect::ReconnectOptions;
+    ///
+    /// let options = ReconnectOptions::new().with_resend_unflushed::<Vec<u8>>(true);
+    /// ```
+    pub fn with_resend_unflushed<I>(mut self, enabled: bool) -> Self
+    where
+        I: 'static + Send + Clone,
+    {
+        self.0.unflushed_item_cloner = if enabled {
+            Some(Arc::new(|item| {
+                let item = item.downcast_ref::<I>()?;
+                Some(Box::new(item.clone()) as Box<dyn Any + Send>)
+            }))
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Regenerate the ctor argument before every reconnect attempt, e.g. to refresh an auth token.
+    /// Its output replaces the ctor argument the stream was connected with for that attempt.
+    ///
+    /// The returned type must be the ctor argument type `C` of the stream,
+    /// otherwise the refreshed value is discarded with an error logged, and the previous ctor argument is used.
+    /// # Examples
+    ///
+    /// ```
+    /// use stream_reconnect::ReconnectOptions;
+    ///
+    /// # async fn fetch_token() -> String { String::from("secret") }
+    /// let options = ReconnectOptions::new().with_ctor_arg_refresh(|| async {
+    ///     format!("wss://example.com/?token={}", fetch_token().await)
+    /// });
+    /// ```
+    pub fn with_ctor_arg_refresh<C, F, Fut>(mut self, refresh: F) -> Self
+    where
+        C: 'static + Send,
+        F: 'static + Send + Sync + Fn() -> Fut,
+        Fut: 'static + Send + Future<Output = C>,
+    {
+        self.0.ctor_arg_refresh = Some(Arc::new(move || {
+            refresh()
+                .map(|ctor_arg| Box::new(ctor_arg) as Box<dyn Any + Send>)
+                .boxed()
+        }));
+        self
+    }
+
+    /// Invoked with each item of type `I` read from the underlying stream and yielded to the consumer,
+    /// e.g. to count or sample the traffic. Items consumed by the stream itself, such as the ones
+    /// [dropped](crate::ItemAction::Drop) or considered a disconnect, are not passed to it.
+    ///
+    /// It is never invoked if `I` is not the item type read from the stream.
+    /// # Examples
+    ///
+    /// ```
+    /// use stream_reconnect::ReconnectOptions;
+    ///
+    /// let options = ReconnectOptions::new().with_on_item_received(|item: &Vec<u8>| {
+    ///     println!("received {} bytes", item.len());
+    /// });
+    /// ```
+    pub fn with_on_item_received<I: 'static>(
+        mut self,
+        cb: impl Fn(&I) + 'static + Send + Sync,
+    ) -> Self {
+        self.0.on_item_received = Some(Arc::new(move |item| {
+            if let Some(item) = item.downcast_ref::<I>() {
+                cb(item)
+            }
+        }));
+        self
+    }
+
+    /// Invoked with each item of type `I` as it is handed to the underlying sink,
+    /// including the items sent from the [outbound buffer](Self::with_outbound_buffer) once reconnected.
+    /// Items which are [resent](Self::with_resend_unflushed) after a reconnect are passed to it again.
+    ///
+    /// Items of another type, sent through another `Sink` impl of the underlying stream, are not passed to it.
+    pub fn with_on_item_sent<I: 'static>(
+        mut self,
+        cb: impl Fn(&I) + 'static + Send + Sync,
+    ) -> Self {
+        self.0.on_item_sent = Some(Arc::new(move |item| {
+            if let Some(item) = item.downcast_ref::<I>() {
+                cb(item)
+            }
+        }));
+        self
+    }
+
+    /// A name identifying the [ReconnectStream](crate::ReconnectStream).
+    /// The errors of the stream are wrapped in [Named](crate::ReconnectError::Named) to carry it.
+    /// With the `tracing` feature enabled, it is recorded on the span of each reconnection episode.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.0.name = Some(name.into());
+        self
+    }
+
+    /// Invoked when the [ReconnectStream](crate::ReconnectStream) establishes a connection,
+    /// before any item of the new connection is yielded.
+    pub fn with_on_connect_callback(self, cb: impl Fn() + 'static + Send + Sync) -> Self {
+        self.with_on_connect_callback_ctx(move |_| cb())
+    }
+
     /// Invoked when the [ReconnectStream](crate::ReconnectStream) loses its active connection
-    pub fn with_on_disconnect_callback(mut self, cb: impl Fn() + 'static + Send + Sync) -> Self {
+    pub fn with_on_disconnect_callback(self, cb: impl Fn() + 'static + Send + Sync) -> Self {
+        self.with_on_disconnect_callback_ctx(move |_| cb())
+    }
+
+    /// Invoked when the [ReconnectStream](crate::ReconnectStream) starts the first reconnect attempt
+    /// after losing its connection, once the delay before it has elapsed.
+    /// Unlike the `on_disconnect_callback`, it is not invoked when no attempt is made,
+    /// such as when reconnecting is paused or the retries are exhausted.
+    pub fn with_on_reconnect_start_callback(self, cb: impl Fn() + 'static + Send + Sync) -> Self {
+        self.with_on_reconnect_start_callback_ctx(move |_| cb())
+    }
+
+    /// Invoked when the [ReconnectStream](crate::ReconnectStream) fails a connection attempt
+    pub fn with_on_connect_fail_callback(self, cb: impl Fn() + 'static + Send + Sync) -> Self {
+        self.with_on_connect_fail_callback_ctx(move |_| cb())
+    }
+
+    /// Invoked once when the [ReconnectStream](crate::ReconnectStream) gives up reconnecting and becomes dead,
+    /// or when the initial connect fails for good, with a [ReconnectSummary] of what was tried.
+    /// It is not invoked when the stream is shut down or closed.
+    /// # Examples
+    ///
+    /// ```
+    /// use stream_reconnect::ReconnectOptions;
+    ///
+    /// let options = ReconnectOptions::new().with_on_give_up_callback(|summary| {
+    ///     eprintln!(
+    ///         "giving up after {} attempts over {:?}, last error: {:?}",
+    ///         summary.attempts, summary.total_elapsed, summary.last_error
+    ///     );
+    /// });
+    /// ```
+    pub fn with_on_give_up_callback(
+        mut self,
+        cb: impl Fn(&ReconnectSummary<'_>) + 'static + Send + Sync,
+    ) -> Self {
+        self.0.on_give_up_callback = Arc::new(cb);
+        self
+    }
+
+    /// Invoked with the number of delays left in the retries iterator once it is down to `threshold` of them,
+    /// so that operators are warned before the stream gives up.
+    /// Zero means that the attempt about to be made is the last one the iterator allows.
+    ///
+    /// This only counts the delays of the iterator, so it never fires for an infinite iterator or a
+    /// [backoff fn](Self::with_backoff_fn), nor when giving up because of the other limits.
+    /// It fires at most once until the next successful connect.
+    /// The count is exact for iterators which tell their size, such as those of a `Vec`.
+    /// For other iterators, only the last delay can be told, by pulling it one attempt ahead of time.
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use stream_reconnect::ReconnectOptions;
+    ///
+    /// let options = ReconnectOptions::new()
+    ///     .with_retries_generator(|| vec![Duration::from_secs(1); 10])
+    ///     .with_on_near_exhaustion_callback(2, |remaining| {
+    ///         eprintln!("only {} reconnect attempts left", remaining);
+    ///     });
+    /// ```
+    pub fn with_on_near_exhaustion_callback(
+        mut self,
+        threshold: usize,
+        cb: impl Fn(usize) + 'static + Send + Sync,
+    ) -> Self {
+        self.0.on_near_exhaustion = Some((threshold, Arc::new(cb)));
+        self
+    }
+
+    /// Same as [with_on_connect_callback](Self::with_on_connect_callback),
+    /// but the callback receives the [ReconnectContext] of the episode which just ended.
+    pub fn with_on_connect_callback_ctx(
+        mut self,
+        cb: impl Fn(&ReconnectContext) + 'static + Send + Sync,
+    ) -> Self {
+        self.0.on_connect_callback = Arc::new(cb);
+        self
+    }
+
+    /// Same as [with_on_connect_callback_ctx](Self::with_on_connect_callback_ctx), but the callback returns a future
+    /// which completes before any item of the new connection is yielded, e.g. to send a subscription and await its flush.
+    /// It is invoked after the synchronous `on_connect_callback`, and the initial connect only resolves once it completes.
+    ///
+    /// While the future is pending, reads are held back, but items can still be sent.
+    /// # Examples
+    ///
+    /// ```
+    /// use stream_reconnect::ReconnectOptions;
+    ///
+    /// # async fn subscribe() {}
+    /// let options = ReconnectOptions::new().with_on_connect_async(|_| async {
+    ///     subscribe().await;
+    /// });
+    /// ```
+    pub fn with_on_connect_async<F, Fut>(mut self, cb: F) -> Self
+    where
+        F: 'static + Send + Sync + Fn(&ReconnectContext) -> Fut,
+        Fut: 'static + Send + Future<Output = ()>,
+    {
+        self.0.on_connect_async = Some(Arc::new(move |ctx| cb(ctx).boxed()));
+        self
+    }
+
+    /// Same as [with_on_disconnect_callback](Self::with_on_disconnect_callback),
+    /// but the callback receives the [ReconnectContext] of the episode which just started.
+    pub fn with_on_disconnect_callback_ctx(
+        mut self,
+        cb: impl Fn(&ReconnectContext) + 'static + Send + Sync,
+    ) -> Self {
         self.0.on_disconnect_callback = Arc::new(cb);
         self
     }
 
-    /// Invoked when the [ReconnectStream](crate::ReconnectStream) fails a connection attempt
-    pub fn with_on_connect_fail_callback(mut self, cb: impl Fn() + 'static + Send + Sync) -> Self {
+    /// Invoked when the [ReconnectStream](crate::ReconnectStream) establishes a connection, before the
+    /// `on_connect_callback`, to decide whether to keep it. When it returns [ControlFlow::Terminate],
+    /// the connection is dropped and the stream becomes dead, or the initial connect fails with
+    /// [ReconnectError::Terminated](crate::ReconnectError::Terminated).
+    pub fn with_on_connect_callback_with_control(
+        mut self,
+        cb: impl Fn(&ReconnectContext) -> ControlFlow + 'static + Send + Sync,
+    ) -> Self {
+        self.0.on_connect_control = Some(Arc::new(cb));
+        self
+    }
+
+    /// Invoked when the [ReconnectStream](crate::ReconnectStream) loses its active connection,
+    /// before the `on_disconnect_callback`, to decide whether to reconnect.
+    /// When it returns [ControlFlow::Terminate], the stream becomes dead without pulling any delay
+    /// from the retries, so the context never has a `next_delay`.
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::Arc;
+    /// use stream_reconnect::{ControlFlow, ReconnectOptions};
+    ///
+    /// let shutting_down = Arc::new(AtomicBool::new(false));
+    /// let flag = shutting_down.clone();
+    /// let options = ReconnectOptions::new().with_on_disconnect_callback_with_control(move |_| {
+    ///     if flag.load(Ordering::SeqCst) {
+    ///         ControlFlow::Terminate
+    ///     } else {
+    ///         ControlFlow::Continue
+    ///     }
+    /// });
+    /// ```
+    pub fn with_on_disconnect_callback_with_control(
+        mut self,
+        cb: impl Fn(&ReconnectContext) -> ControlFlow + 'static + Send + Sync,
+    ) -> Self {
+        self.0.on_disconnect_control = Some(Arc::new(cb));
+        self
+    }
+
+    /// Same as [with_on_reconnect_start_callback](Self::with_on_reconnect_start_callback),
+    /// but the callback receives the [ReconnectContext] of the attempt being started.
+    pub fn with_on_reconnect_start_callback_ctx(
+        mut self,
+        cb: impl Fn(&ReconnectContext) + 'static + Send + Sync,
+    ) -> Self {
+        self.0.on_reconnect_start_callback = Arc::new(cb);
+        self
+    }
+
+    /// Same as [with_on_connect_fail_callback](Self::with_on_connect_fail_callback),
+    /// but the callback receives the [ReconnectContext] of the failed attempt.
+    /// # Examples
+    ///
+    /// ```
+    /// use stream_reconnect::ReconnectOptions;
+    ///
+    /// let options = ReconnectOptions::new().with_on_connect_fail_callback_ctx(|ctx| {
+    ///     eprintln!(
+    ///         "attempt {} failed after {:?} of downtime, retrying in {:?}",
+    ///         ctx.attempt, ctx.downtime, ctx.next_delay
+    ///     );
+    /// });
+    /// ```
+    pub fn with_on_connect_fail_callback_ctx(
+        mut self,
+        cb: impl Fn(&ReconnectContext) + 'static + Send + Sync,
+    ) -> Self {
         self.0.on_connect_fail_callback = Arc::new(cb);
         self
     }
+
+    /// Invoked with the error returned by [UnderlyingStream::establish](crate::UnderlyingStream::establish)
+    /// when the [ReconnectStream](crate::ReconnectStream) fails a connection attempt.
+    /// It is invoked alongside the `on_connect_fail_callback`, which is not replaced by this one.
+    ///
+    /// The error can be downcast to the error type of the stream, while timed out and panicked attempts
+    /// are reported as a [ReconnectError](crate::ReconnectError) of it.
+    /// Running out of retries is not reported here,
+    /// the stream yields `None` and the sink fails with
+    /// [ReconnectsExhausted](crate::ReconnectError::ReconnectsExhausted) instead.
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use stream_reconnect::ReconnectOptions;
+    ///
+    /// let options = ReconnectOptions::new().with_on_connect_fail_callback_err(|err| {
+    ///     if let Some(err) = err.downcast_ref::<io::Error>() {
+    ///         eprintln!("connection attempt failed: {:?}", err.kind());
+    ///     }
+    /// });
+    /// ```
+    pub fn with_on_connect_fail_callback_err(
+        mut self,
+        cb: impl Fn(&(dyn Error + 'static)) + 'static + Send + Sync,
+    ) -> Self {
+        self.0.on_connect_fail_err_callback = Arc::new(cb);
+        self
+    }
+
+    /// Same as [with_on_connect_callback_ctx](Self::with_on_connect_callback_ctx),
+    /// but accepts a stateful `FnMut` callback.
+    /// # Examples
+    ///
+    /// ```
+    /// use stream_reconnect::ReconnectOptions;
+    ///
+    /// let mut connects = 0;
+    /// let options = ReconnectOptions::new().with_on_connect_callback_mut(move |_| {
+    ///     connects += 1;
+    ///     eprintln!("connected {} times", connects);
+    /// });
+    /// ```
+    pub fn with_on_connect_callback_mut(
+        mut self,
+        cb: impl FnMut(&ReconnectContext) + 'static + Send,
+    ) -> Self {
+        self.0.on_connect_callback = mut_callback(cb);
+        self
+    }
+
+    /// Same as [with_on_disconnect_callback_ctx](Self::with_on_disconnect_callback_ctx),
+    /// but accepts a stateful `FnMut` callback.
+    pub fn with_on_disconnect_callback_mut(
+        mut self,
+        cb: impl FnMut(&ReconnectContext) + 'static + Send,
+    ) -> Self {
+        self.0.on_disconnect_callback = mut_callback(cb);
+        self
+    }
+
+    /// Same as [with_on_connect_fail_callback_ctx](Self::with_on_connect_fail_callback_ctx),
+    /// but accepts a stateful `FnMut` callback.
+    pub fn with_on_connect_fail_callback_mut(
+        mut self,
+        cb: impl FnMut(&ReconnectContext) + 'static + Send,
+    ) -> Self {
+        self.0.on_connect_fail_callback = mut_callback(cb);
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ConfigError, ReconnectOptions};
+    use std::time::Duration;
+
+    #[test]
+    fn test_debug_renders_placeholders() {
+        let options = ReconnectOptions::new()
+            .with_max_attempts(3)
+            .with_name("feed");
+        let debug = format!("{:?}", options);
+        assert!(debug.contains("retries_to_attempt_fn: \"<fn>\""));
+        assert!(debug.contains("on_connect_callback: \"<callback>\""));
+        assert!(debug.contains("max_attempts: Some(3)"));
+        assert!(debug.contains("name: Some(\"feed\")"));
+    }
+
+    #[test]
+    fn test_initial_jitter_is_bounded_and_seedable() {
+        let max = Duration::from_millis(100);
+        let a = ReconnectOptions::new().with_initial_jitter_seeded(max, 7);
+        let b = ReconnectOptions::new().with_initial_jitter_seeded(max, 7);
+        for _ in 0..100 {
+            let jitter = a.initial_jitter();
+            assert!(jitter <= max);
+            assert_eq!(jitter, b.initial_jitter());
+        }
+        assert_eq!(ReconnectOptions::new().initial_jitter(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_peek_schedule() {
+        let secs = |secs: &[u64]| {
+            secs.iter()
+                .map(|&s| Duration::from_secs(s))
+                .collect::<Vec<_>>()
+        };
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_secs(1), Duration::from_secs(100)]);
+        assert_eq!(options.peek_schedule(5), secs(&[1, 100]));
+        let options = options
+            .with_min_delay(Duration::from_secs(2))
+            .with_max_delay(Duration::from_secs(50));
+        assert_eq!(options.peek_schedule(1), secs(&[2]));
+        assert_eq!(options.peek_schedule(5), secs(&[2, 50]));
+
+        let options =
+            ReconnectOptions::new().with_backoff_fn(|attempt| Duration::from_secs(1 << attempt));
+        assert_eq!(options.peek_schedule(4), secs(&[1, 2, 4, 8]));
+    }
+
+    #[test]
+    fn test_fixed_interval() {
+        let interval = Duration::from_secs(2);
+        let options = ReconnectOptions::new().with_fixed_interval(interval, Some(3));
+        assert_eq!(options.peek_schedule(5), vec![interval; 3]);
+        let options = ReconnectOptions::new().with_fixed_interval(interval, None);
+        assert_eq!(options.peek_schedule(100), vec![interval; 100]);
+    }
+
+    #[test]
+    fn test_rng_seeds_all_jitter() {
+        let options = || {
+            ReconnectOptions::new()
+                .with_exponential_backoff(Duration::from_secs(1), 2.0, Duration::from_secs(60))
+                .with_full_jitter()
+                .with_initial_jitter(Duration::from_secs(1))
+                .with_rng_seed(7)
+        };
+        let (a, b) = (options(), options());
+        for _ in 0..3 {
+            assert_eq!(
+                a.retries().take(10).collect::<Vec<_>>(),
+                b.retries().take(10).collect::<Vec<_>>()
+            );
+            assert_eq!(a.initial_jitter(), b.initial_jitter());
+        }
+        let other = options().with_rng_seed(8);
+        assert_ne!(
+            a.retries().take(10).collect::<Vec<_>>(),
+            other.retries().take(10).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_default_options_are_valid() {
+        assert_eq!(ReconnectOptions::new().validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_empty_retries_are_invalid() {
+        let options = ReconnectOptions::new().with_retries_generator(Vec::new);
+        assert_eq!(options.validate(), Err(ConfigError::EmptyRetries));
+    }
+
+    #[test]
+    fn test_validate_does_not_draw_from_the_rng() {
+        let options = ReconnectOptions::new().with_full_jitter().with_rng_seed(7);
+        let expected: Vec<_> = ReconnectOptions::new()
+            .with_full_jitter()
+            .with_rng_seed(7)
+            .retries()
+            .take(5)
+            .collect();
+        assert_eq!(options.validate(), Ok(()));
+        assert_eq!(options.retries().take(5).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn test_zero_values_are_invalid() {
+        assert_eq!(
+            ReconnectOptions::new().with_max_attempts(0).validate(),
+            Err(ConfigError::ZeroMaxAttempts)
+        );
+        assert_eq!(
+            ReconnectOptions::new()
+                .with_max_reconnect_duration(Duration::ZERO)
+                .validate(),
+            Err(ConfigError::ZeroMaxReconnectDuration)
+        );
+        assert_eq!(
+            ReconnectOptions::new()
+                .with_first_connect_grace(0)
+                .validate(),
+            Err(ConfigError::ZeroFirstConnectGrace)
+        );
+        assert_eq!(
+            ReconnectOptions::new()
+                .with_attempt_rate_limit(0, Duration::from_secs(1))
+                .validate(),
+            Err(ConfigError::ZeroAttemptRateLimit)
+        );
+        assert_eq!(
+            ReconnectOptions::new()
+                .with_circuit_breaker(0, Duration::from_secs(1))
+                .validate(),
+            Err(ConfigError::ZeroCircuitBreakerThreshold)
+        );
+        assert_eq!(
+            ReconnectOptions::new()
+                .with_connect_timeout(Duration::ZERO)
+                .validate(),
+            Err(ConfigError::ZeroConnectTimeout)
+        );
+        assert_eq!(
+            ReconnectOptions::new()
+                .with_idle_timeout(Duration::ZERO)
+                .validate(),
+            Err(ConfigError::ZeroIdleTimeout)
+        );
+        assert_eq!(
+            ReconnectOptions::new()
+                .with_flush_timeout(Duration::ZERO)
+                .validate(),
+            Err(ConfigError::ZeroFlushTimeout)
+        );
+        assert_eq!(
+            ReconnectOptions::new()
+                .with_disconnect_error_threshold(0)
+                .validate(),
+            Err(ConfigError::ZeroDisconnectErrorThreshold)
+        );
+        assert_eq!(
+            ReconnectOptions::new().with_inbound_buffer(0).validate(),
+            Err(ConfigError::ZeroInboundBufferCapacity)
+        );
+        assert_eq!(
+            ReconnectOptions::new().with_outbound_buffer(0).validate(),
+            Err(ConfigError::ZeroOutboundBufferCapacity)
+        );
+        assert_eq!(
+            ReconnectOptions::new().with_flush_batch_size(0).validate(),
+            Err(ConfigError::ZeroFlushBatchSize)
+        );
+        assert_eq!(
+            ReconnectOptions::new()
+                .with_outbound_buffer(1)
+                .with_fail_fast_send(true)
+                .validate(),
+            Err(ConfigError::FailFastSendWithOutboundBuffer)
+        );
+        assert_eq!(
+            ReconnectOptions::new()
+                .with_min_delay(Duration::from_secs(2))
+                .with_max_delay(Duration::from_secs(1))
+                .validate(),
+            Err(ConfigError::MinDelayAboveMaxDelay)
+        );
+    }
 }
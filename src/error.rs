@@ -0,0 +1,116 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// Errors surfaced by [ReconnectStream](crate::ReconnectStream).
+///
+/// Transient failures of the underlying stream are reported as [Underlying](Self::Underlying),
+/// while the other variants tell that the stream is not going to recover on its own.
+#[derive(Debug)]
+pub enum ReconnectError<E> {
+    /// The underlying stream returned an error.
+    Underlying(E),
+    /// All reconnect attempts have failed. The stream will not reconnect anymore.
+    ReconnectsExhausted,
+    /// The initial connection could not be established.
+    InitialConnectFailed(E),
+    /// The stream has been shut down and will not reconnect anymore.
+    Shutdown,
+    /// The connection is down, so the item was not accepted, as requested by
+    /// [with_fail_fast_send](crate::ReconnectOptions::with_fail_fast_send).
+    Disconnected,
+    /// The outbound buffer is full, so the item was not accepted.
+    OutboundBufferFull,
+    /// The item is not of the type of the items held in the outbound buffer, so it was not accepted.
+    /// This only happens to a stream whose underlying stream is a [Sink](futures::Sink) of several item types,
+    /// while items of another type are buffered.
+    ItemTypeMismatch,
+    /// A connect attempt did not complete within the connect timeout.
+    ConnectTimeout,
+    /// A connect attempt panicked, with the panic message.
+    /// Panics are only caught with the `catch-panic` feature, otherwise they propagate through the poll.
+    EstablishPanic(String),
+    /// A callback [requested](crate::ControlFlow::Terminate) to give up the connection.
+    Terminated,
+    /// Too many reconnect attempts failed in a row, so sends are rejected until a probe attempt succeeds.
+    CircuitOpen,
+    /// The error of a stream given a [name](crate::ReconnectOptions::with_name), which is displayed along with the error.
+    Named {
+        name: String,
+        error: Box<ReconnectError<E>>,
+    },
+}
+
+impl<E> ReconnectError<E> {
+    /// Returns the name of the stream which failed, if it was given one.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            ReconnectError::Named { name, .. } => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Returns the error without the stream name.
+    pub fn unnamed(&self) -> &ReconnectError<E> {
+        match self {
+            ReconnectError::Named { error, .. } => error,
+            error => error,
+        }
+    }
+
+    pub(crate) fn named(self, name: Option<&str>) -> Self {
+        match name {
+            Some(name) => ReconnectError::Named {
+                name: name.to_string(),
+                error: Box::new(self),
+            },
+            None => self,
+        }
+    }
+}
+
+impl<E> From<E> for ReconnectError<E> {
+    fn from(err: E) -> Self {
+        ReconnectError::Underlying(err)
+    }
+}
+
+impl<E: Display> Display for ReconnectError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ReconnectError::Underlying(err) => err.fmt(f),
+            ReconnectError::ReconnectsExhausted => {
+                f.write_str("disconnected, reconnect attempts have been exhausted")
+            }
+            ReconnectError::InitialConnectFailed(err) => {
+                write!(f, "initial connection failed: {}", err)
+            }
+            ReconnectError::Shutdown => f.write_str("stream has been shut down"),
+            ReconnectError::Disconnected => f.write_str("disconnected, the item was not sent"),
+            ReconnectError::OutboundBufferFull => f.write_str("outbound buffer is full"),
+            ReconnectError::ItemTypeMismatch => {
+                f.write_str("item type does not match the buffered items")
+            }
+            ReconnectError::ConnectTimeout => f.write_str("connect attempt timed out"),
+            ReconnectError::EstablishPanic(message) => {
+                write!(f, "connect attempt panicked: {}", message)
+            }
+            ReconnectError::Terminated => f.write_str("terminated by a callback"),
+            ReconnectError::CircuitOpen => {
+                f.write_str("circuit is open, the connection keeps failing")
+            }
+            ReconnectError::Named { name, error } => write!(f, "{}: {}", name, error),
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for ReconnectError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReconnectError::Underlying(err) | ReconnectError::InitialConnectFailed(err) => {
+                Some(err)
+            }
+            ReconnectError::Named { error, .. } => error.source(),
+            _ => None,
+        }
+    }
+}
@@ -9,7 +9,7 @@
 //!
 //! *This crate is a fork of [stubborn-io](https://github.com/craftytrickster/stubborn-io).*
 //!
-//! *Minimum supported rust version: 1.43.1*
+//! *Minimum supported rust version: 1.82*
 //!
 //! ### Runtime Support
 //!
@@ -21,10 +21,28 @@
 //! stream-reconnect = { version = "0.3", default-features = false, features = ["async-std"] }
 //! ```
 //!
+//! Exactly one of the `tokio` and `async-std` features must be enabled, otherwise the crate does not compile.
+//!
 //! ## Feature Gates
 //!
 //! `not-send` - allow the establish function to be non thread-safe.
 //!
+//! `tracing` - emit [tracing](https://docs.rs/tracing) spans and events for the reconnect lifecycle.
+//! Each reconnection episode gets a span carrying the name set by
+//! [ReconnectOptions::with_name], the current attempt and its delay.
+//!
+//! `testing` - provide the `testing` module with a scriptable mock stream to test code using [ReconnectStream].
+//!
+//! `test-util` - provide the [TestClock] and `ReconnectStream::with_clock`,
+//! to test code using [ReconnectStream] without waiting for real time to elapse.
+//!
+//! `tungstenite` - provide the `websocket` module and the
+//! `UnderlyingStream::should_reconnect_on_close` hook to tell WebSocket close codes apart.
+//! With `tokio`, it also provides `websocket::ReconnectWebSocket`, ready to use over `tokio-tungstenite`.
+//!
+//! `catch-panic` - catch a panic of a connect attempt and retry it like a failed attempt,
+//! reporting it as [ReconnectError::EstablishPanic].
+//!
 //! ### Motivations (preserved from stubborn-io)
 //! This crate was created because I was working on a service that needed to fetch data from a remote server
 //! via a tokio TcpConnection. It normally worked perfectly (as does all of my code ☺), but every time the
@@ -39,7 +57,6 @@
 //! ```rust
 //! use stream_reconnect::{UnderlyingStream, ReconnectStream};
 //! use std::future::Future;
-//! use std::io;
 //! use std::pin::Pin;
 //! use tokio::net::TcpStream;
 //! # use tokio::net::TcpListener;
@@ -85,11 +102,6 @@
 //!             false
 //!         }
 //!     }
-//!
-//!     // Return "Exhausted" if all retry attempts are failed.
-//!     fn exhaust_err() -> WsError {
-//!         WsError::Io(io::Error::new(io::ErrorKind::Other, "Exhausted"))
-//!     }
 //! }
 //!
 //! # #[cfg(not(feature = "not-send"))]
@@ -123,10 +135,52 @@
 //! # fn main() {}
 //! ```
 
+pub use crate::boxed::BoxedReconnectStream;
+pub use crate::cancel::CancelToken;
+#[cfg(feature = "test-util")]
+pub use crate::clock::TestClock;
 #[doc(inline)]
-pub use crate::config::ReconnectOptions;
-pub use crate::stream::{ReconnectStream, UnderlyingStream};
+pub use crate::config::{
+    ConfigError, ControlFlow, EndpointPolicy, InitialConnectSendPolicy, OutboundOverflowPolicy,
+    ReconnectContext, ReconnectOptions, ReconnectSummary,
+};
+pub use crate::error::ReconnectError;
+pub use crate::limiter::ReconnectLimiter;
+pub use crate::paired::Paired;
+pub use crate::split::{ReconnectReader, ReconnectSink};
+pub use crate::stats::{ConnectionStats, ReconnectStats};
+pub use crate::stream::{
+    is_io_disconnect, ConnEvent, ConnectionStatus, DisconnectKind, ItemAction, ReconnectStream,
+    UnderlyingStream,
+};
 
+mod boxed;
+mod cancel;
+mod clock;
 pub mod config;
+mod error;
+mod limiter;
+mod paired;
+mod runtime;
+mod split;
+mod stats;
 pub mod strategies;
 mod stream;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "tungstenite")]
+pub mod websocket;
+
+/// An alias of [strategies], which holds the backoff and jitter strategies.
+///
+/// [strategies] is the canonical path, used throughout the documentation; both paths name the same items.
+/// # Examples
+///
+/// ```
+/// use stream_reconnect::{backoff, strategies};
+///
+/// let _: strategies::ExpBackoffStrategy = backoff::ExpBackoffStrategy::default();
+/// ```
+pub mod backoff {
+    pub use crate::strategies::*;
+}
@@ -0,0 +1,95 @@
+use std::convert::TryFrom;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// A reconnect budget shared by several [ReconnectStream](crate::ReconnectStream)s, so that a fleet of streams
+/// losing the same backend does not flood it with reconnect attempts, as set by
+/// [ReconnectOptions::with_reconnect_limiter](crate::ReconnectOptions::with_reconnect_limiter).
+///
+/// It is a token bucket of `max` attempts, refilled at the rate of `max` attempts per `per`.
+/// Once the budget is exhausted, each stream waits for a token before its next attempt,
+/// on top of its own backoff delay and [attempt rate limit](crate::ReconnectOptions::with_attempt_rate_limit).
+/// Every connect attempt takes a token, including those made on demand by
+/// [migrate](crate::ReconnectStream::migrate), [Paired](crate::Paired) streams and the streams created by
+/// [reconfigure_clone](crate::ReconnectStream::reconfigure_clone), and the first attempt of the initial connect.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use stream_reconnect::{ReconnectLimiter, ReconnectOptions};
+///
+/// // No more than 20 reconnect attempts per second across all the streams.
+/// let limiter = Arc::new(ReconnectLimiter::new(20, Duration::from_secs(1)));
+/// let options = ReconnectOptions::new().with_reconnect_limiter(limiter.clone());
+/// let other_options = ReconnectOptions::new().with_reconnect_limiter(limiter);
+/// ```
+#[derive(Debug)]
+pub struct ReconnectLimiter {
+    /// Time to refill one token
+    interval: Duration,
+    /// How far ahead of the refill an attempt may start, which allows bursts of the bucket capacity
+    burst: Duration,
+    /// When the bucket is refilled, given the attempts granted so far
+    refilled_at: Mutex<Option<Instant>>,
+}
+
+impl ReconnectLimiter {
+    /// Allows `max` attempts per `per` across the streams sharing this limiter, in bursts of up to `max` attempts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is zero.
+    pub fn new(max: usize, per: Duration) -> Self {
+        assert!(
+            max > 0,
+            "a reconnect limiter must allow at least one attempt"
+        );
+        let interval = per / u32::try_from(max).unwrap_or(u32::MAX);
+        ReconnectLimiter {
+            interval,
+            burst: interval.saturating_mul(u32::try_from(max - 1).unwrap_or(u32::MAX)),
+            refilled_at: Mutex::new(None),
+        }
+    }
+
+    /// Takes a token for an attempt meant to start at `start`, and returns when it can start.
+    pub(crate) fn reserve(&self, start: Instant) -> Instant {
+        let mut refilled_at = self
+            .refilled_at
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let refill = refilled_at.map_or(start, |refilled_at| refilled_at.max(start));
+        let granted = refill
+            .checked_sub(self.burst)
+            .map_or(start, |earliest| earliest.max(start));
+        *refilled_at = Some(refill + self.interval);
+        granted
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_reserve_allows_bursts_then_the_refill_rate() {
+        let limiter = ReconnectLimiter::new(2, Duration::from_secs(2));
+        let now = Instant::now();
+        let granted: Vec<_> = (0..4)
+            .map(|_| limiter.reserve(now).duration_since(now))
+            .collect();
+        assert_eq!(
+            granted,
+            vec![
+                Duration::ZERO,
+                Duration::ZERO,
+                Duration::from_secs(1),
+                Duration::from_secs(2)
+            ]
+        );
+        let later = now + Duration::from_secs(10);
+        assert_eq!(limiter.reserve(later), later);
+    }
+}
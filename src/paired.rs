@@ -0,0 +1,272 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll, Waker};
+
+use futures::{Sink, Stream};
+use log::info;
+
+use crate::error::ReconnectError;
+use crate::stream::{ConnectionStatus, ReconnectStream, UnderlyingStream};
+
+/// The coordination state shared by the two halves of a pair.
+#[derive(Default)]
+struct Shared {
+    /// The half which lost its connection and runs the backoff for both, while reconnecting.
+    leader: Option<usize>,
+    /// Set once a half gave up, so that the other half gives up as well.
+    dead: bool,
+    wakers: [Option<Waker>; 2],
+}
+
+impl Shared {
+    fn wake(&self, index: usize) {
+        if let Some(waker) = &self.wakers[index] {
+            waker.wake_by_ref();
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Idle,
+    /// Lost its connection and reconnects following its retries.
+    Leading,
+    /// Dropped its connection and waits for the leader to reconnect.
+    Following,
+    /// Reconnects on its own after the leader reconnected.
+    CatchingUp,
+}
+
+/// One of the two [ReconnectStream]s paired by [ReconnectStream::pair_with],
+/// which reconnect together with a shared backoff.
+///
+/// When either stream loses its connection, it reconnects following its retries as usual,
+/// while the other one drops its connection and waits without consuming its own retries.
+/// Once the first one is reconnected, the other one attempts to reconnect right away,
+/// and only follows its own retries if this attempt fails.
+/// So the endpoint only sees one backoff schedule, instead of one for each stream.
+///
+/// If either stream gives up, the other one gives up as well.
+///
+/// The other stream only notices the disconnect the next time it is polled,
+/// e.g. right away if a read is pending, or at the next send for a write half.
+pub struct Paired<T, C, I, E>
+where
+    T: UnderlyingStream<C, I, E>,
+    C: Clone + Send + Unpin,
+    E: Error,
+{
+    stream: ReconnectStream<T, C, I, E>,
+    shared: Arc<Mutex<Shared>>,
+    index: usize,
+    role: Role,
+}
+
+impl<T, C, I, E> ReconnectStream<T, C, I, E>
+where
+    T: UnderlyingStream<C, I, E>,
+    C: Clone + Send + Unpin + 'static,
+    I: Unpin,
+    E: Error + Unpin + 'static,
+{
+    /// Pairs this stream with `other`, which connects to the same endpoint,
+    /// so that a disconnect on either one makes both reconnect, with a shared backoff.
+    /// This suits a transport which uses separate connections for reading and writing.
+    ///
+    /// See [Paired] for how the reconnections are coordinated.
+    #[allow(clippy::type_complexity)]
+    pub fn pair_with<T2, I2>(
+        self,
+        other: ReconnectStream<T2, C, I2, E>,
+    ) -> (Paired<T, C, I, E>, Paired<T2, C, I2, E>)
+    where
+        T2: UnderlyingStream<C, I2, E>,
+        I2: Unpin,
+    {
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        (
+            Paired::new(self, shared.clone(), 0),
+            Paired::new(other, shared, 1),
+        )
+    }
+}
+
+impl<T, C, I, E> Paired<T, C, I, E>
+where
+    T: UnderlyingStream<C, I, E>,
+    C: Clone + Send + Unpin + 'static,
+    I: Unpin,
+    E: Error + Unpin + 'static,
+{
+    fn new(stream: ReconnectStream<T, C, I, E>, shared: Arc<Mutex<Shared>>, index: usize) -> Self {
+        Paired {
+            stream,
+            shared,
+            index,
+            role: Role::Idle,
+        }
+    }
+
+    /// Catches up with the other stream of the pair before and after each poll.
+    fn sync(&mut self, cx: &Context) {
+        let mut shared = self.shared.lock().unwrap_or_else(PoisonError::into_inner);
+        let other = 1 - self.index;
+        match &shared.wakers[self.index] {
+            Some(waker) if waker.will_wake(cx.waker()) => {}
+            _ => shared.wakers[self.index] = Some(cx.waker().clone()),
+        }
+
+        if shared.dead {
+            self.stream.give_up();
+            return;
+        }
+        if !self.stream.reconnection_enabled() {
+            if self.stream.is_dead() {
+                shared.dead = true;
+                shared.wake(other);
+            } else if shared.leader == Some(self.index) {
+                // shut down while reconnecting, so the other stream reconnects on its own
+                shared.leader = None;
+                shared.wake(other);
+            }
+            return;
+        }
+
+        let connected = self.stream.status() == ConnectionStatus::Connected;
+        match shared.leader {
+            Some(leader) if leader == self.index => {
+                if connected {
+                    shared.leader = None;
+                    self.role = Role::Idle;
+                    shared.wake(other);
+                }
+            }
+            Some(_) => {
+                if self.role != Role::Following {
+                    info!("Paired stream disconnected, waiting for it to reconnect");
+                    self.stream.hold();
+                    self.role = Role::Following;
+                }
+            }
+            None => match self.role {
+                Role::Following => {
+                    info!("Paired stream reconnected, reconnecting now");
+                    self.stream.reconnect_now();
+                    self.role = Role::CatchingUp;
+                }
+                _ if connected => self.role = Role::Idle,
+                Role::CatchingUp => {}
+                Role::Idle | Role::Leading => {
+                    shared.leader = Some(self.index);
+                    self.role = Role::Leading;
+                    shared.wake(other);
+                }
+            },
+        }
+    }
+}
+
+impl<T, C, I, E> Deref for Paired<T, C, I, E>
+where
+    T: UnderlyingStream<C, I, E>,
+    C: Clone + Send + Unpin,
+    E: Error,
+{
+    type Target = ReconnectStream<T, C, I, E>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.stream
+    }
+}
+
+impl<T, C, I, E> DerefMut for Paired<T, C, I, E>
+where
+    T: UnderlyingStream<C, I, E>,
+    C: Clone + Send + Unpin,
+    E: Error,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.stream
+    }
+}
+
+impl<T, C, I, E> fmt::Debug for Paired<T, C, I, E>
+where
+    T: UnderlyingStream<C, I, E>,
+    C: Clone + Send + Unpin + 'static,
+    I: Unpin,
+    E: Error + Unpin + 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Paired")
+            .field("stream", &self.stream)
+            .field("role", &self.role)
+            .finish()
+    }
+}
+
+impl<T, C, I, E> Stream for Paired<T, C, I, E>
+where
+    T: UnderlyingStream<C, I, E>,
+    T::Stream: Stream<Item = I>,
+    C: Clone + Send + Unpin + 'static,
+    I: Unpin + 'static,
+    E: Error + Unpin + 'static,
+{
+    type Item = I;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.sync(cx);
+        let poll = Pin::new(&mut self.stream).poll_next(cx);
+        self.sync(cx);
+        poll
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+impl<T, C, I, I2, E> Sink<I> for Paired<T, C, I2, E>
+where
+    T: UnderlyingStream<C, I2, E>,
+    T::Stream: Sink<I, Error = E>,
+    C: Clone + Send + Unpin + 'static,
+    I: Send + 'static,
+    I2: Unpin,
+    E: Error + Unpin + 'static,
+{
+    type Error = ReconnectError<E>;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.sync(cx);
+        let poll = Pin::new(&mut self.stream).poll_ready(cx);
+        // once ready, the stream must be left as is for the following `start_send`,
+        // the next poll catches up with the other stream instead
+        if !matches!(poll, Poll::Ready(Ok(()))) {
+            self.sync(cx);
+        }
+        poll
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: I) -> Result<(), Self::Error> {
+        Pin::new(&mut self.stream).start_send(item)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.sync(cx);
+        let poll = Pin::new(&mut self.stream).poll_flush(cx);
+        self.sync(cx);
+        poll
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.sync(cx);
+        let poll = Pin::new(&mut self.stream).poll_close(cx);
+        self.sync(cx);
+        poll
+    }
+}
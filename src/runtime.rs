@@ -0,0 +1,58 @@
+//! The timer is the only runtime-specific piece of this crate, so it is abstracted here.
+
+use std::future::Future;
+use std::time::Duration;
+
+use futures::future::{self, BoxFuture, Either};
+use futures::FutureExt;
+
+#[cfg(all(feature = "tokio", feature = "async-std"))]
+compile_error!(
+    "features `tokio` and `async-std` are mutually exclusive, enable exactly one of them"
+);
+
+#[cfg(not(any(feature = "tokio", feature = "async-std")))]
+compile_error!("either feature `tokio` or `async-std` must be enabled");
+
+pub(crate) trait Runtime {
+    fn sleep(duration: Duration) -> BoxFuture<'static, ()>;
+}
+
+#[cfg(feature = "tokio")]
+pub(crate) struct Tokio;
+
+#[cfg(feature = "tokio")]
+impl Runtime for Tokio {
+    fn sleep(duration: Duration) -> BoxFuture<'static, ()> {
+        tokio::time::sleep(duration).boxed()
+    }
+}
+
+#[cfg(feature = "async-std")]
+pub(crate) struct AsyncStd;
+
+#[cfg(feature = "async-std")]
+impl Runtime for AsyncStd {
+    fn sleep(duration: Duration) -> BoxFuture<'static, ()> {
+        async_std::task::sleep(duration).boxed()
+    }
+}
+
+#[cfg(feature = "tokio")]
+type Selected = Tokio;
+#[cfg(all(feature = "async-std", not(feature = "tokio")))]
+type Selected = AsyncStd;
+
+/// Sleeps for `duration` on the runtime selected by the enabled feature.
+pub(crate) fn sleep(duration: Duration) -> BoxFuture<'static, ()> {
+    Selected::sleep(duration)
+}
+
+/// Runs `fut` to completion unless `sleep` completes first, in which case `None` is returned.
+pub(crate) async fn timeout<F: Future>(sleep: BoxFuture<'static, ()>, fut: F) -> Option<F::Output> {
+    futures::pin_mut!(fut);
+    match future::select(fut, sleep).await {
+        Either::Left((output, _)) => Some(output),
+        Either::Right(_) => None,
+    }
+}
@@ -0,0 +1,179 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::DerefMut;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+
+use futures::{Sink, Stream};
+
+use crate::error::ReconnectError;
+use crate::stream::{ReconnectStream, UnderlyingStream};
+
+type Shared<T, C, I, E> = Arc<Mutex<ReconnectStream<T, C, I, E>>>;
+
+/// The write half of a [ReconnectStream], as returned by [ReconnectStream::split].
+pub struct ReconnectSink<T, C, I, E>
+where
+    T: UnderlyingStream<C, I, E>,
+    C: Clone + Send + Unpin,
+    E: Error,
+{
+    stream: Shared<T, C, I, E>,
+}
+
+/// The read half of a [ReconnectStream], as returned by [ReconnectStream::split].
+pub struct ReconnectReader<T, C, I, E>
+where
+    T: UnderlyingStream<C, I, E>,
+    C: Clone + Send + Unpin,
+    E: Error,
+{
+    stream: Shared<T, C, I, E>,
+}
+
+fn lock<T>(stream: &Mutex<T>) -> impl DerefMut<Target = T> + '_ {
+    stream.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl<T, C, I, E> ReconnectStream<T, C, I, E>
+where
+    T: UnderlyingStream<C, I, E>,
+    C: Clone + Send + Unpin + 'static,
+    I: Unpin,
+    E: Error + Unpin + 'static,
+{
+    /// Splits this stream into a write half and a read half, which can be handed to separate tasks.
+    ///
+    /// Both halves share the same connection and reconnection machinery: a disconnect detected by
+    /// either half drops the connection for both, and wakes up the other half, so that a pending read
+    /// does not wait on a dead connection while the write half reconnects.
+    /// Unlike [StreamExt::split](futures::StreamExt::split), the halves can still
+    /// [lock](ReconnectSink::lock) the stream to inspect its state.
+    #[allow(clippy::type_complexity)]
+    pub fn split(self) -> (ReconnectSink<T, C, I, E>, ReconnectReader<T, C, I, E>) {
+        let stream = Arc::new(Mutex::new(self));
+        (
+            ReconnectSink {
+                stream: stream.clone(),
+            },
+            ReconnectReader { stream },
+        )
+    }
+}
+
+impl<T, C, I, E> ReconnectSink<T, C, I, E>
+where
+    T: UnderlyingStream<C, I, E>,
+    C: Clone + Send + Unpin + 'static,
+    I: Unpin,
+    E: Error + Unpin + 'static,
+{
+    /// Locks the shared stream, to inspect its [status](ReconnectStream::status) or [stats](ReconnectStream::stats)
+    /// for instance. The other half is blocked while the lock is held, so it should not be held across an await.
+    pub fn lock(&self) -> impl DerefMut<Target = ReconnectStream<T, C, I, E>> + '_ {
+        lock(&self.stream)
+    }
+
+    /// Puts the halves back together, or gives them back if they do not come from the same [split](ReconnectStream::split).
+    #[allow(clippy::type_complexity)]
+    pub fn reunite(
+        self,
+        reader: ReconnectReader<T, C, I, E>,
+    ) -> Result<ReconnectStream<T, C, I, E>, (Self, ReconnectReader<T, C, I, E>)> {
+        if !Arc::ptr_eq(&self.stream, &reader.stream) {
+            return Err((self, reader));
+        }
+        drop(reader);
+        match Arc::try_unwrap(self.stream) {
+            Ok(stream) => Ok(stream.into_inner().unwrap_or_else(PoisonError::into_inner)),
+            Err(_) => unreachable!("both halves were given"),
+        }
+    }
+}
+
+impl<T, C, I, E> ReconnectReader<T, C, I, E>
+where
+    T: UnderlyingStream<C, I, E>,
+    C: Clone + Send + Unpin + 'static,
+    I: Unpin,
+    E: Error + Unpin + 'static,
+{
+    /// See [ReconnectSink::lock].
+    pub fn lock(&self) -> impl DerefMut<Target = ReconnectStream<T, C, I, E>> + '_ {
+        lock(&self.stream)
+    }
+}
+
+impl<T, C, I, E> fmt::Debug for ReconnectSink<T, C, I, E>
+where
+    T: UnderlyingStream<C, I, E>,
+    C: Clone + Send + Unpin + 'static,
+    I: Unpin,
+    E: Error + Unpin + 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ReconnectSink").field(&*self.lock()).finish()
+    }
+}
+
+impl<T, C, I, E> fmt::Debug for ReconnectReader<T, C, I, E>
+where
+    T: UnderlyingStream<C, I, E>,
+    C: Clone + Send + Unpin + 'static,
+    I: Unpin,
+    E: Error + Unpin + 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ReconnectReader")
+            .field(&*self.lock())
+            .finish()
+    }
+}
+
+impl<T, C, I, E> Stream for ReconnectReader<T, C, I, E>
+where
+    T: UnderlyingStream<C, I, E>,
+    T::Stream: Stream<Item = I>,
+    C: Clone + Send + Unpin + 'static,
+    I: Unpin + 'static,
+    E: Error + Unpin + 'static,
+{
+    type Item = I;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut *self.lock()).poll_next(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.lock().size_hint()
+    }
+}
+
+impl<T, C, I, I2, E> Sink<I> for ReconnectSink<T, C, I2, E>
+where
+    T: UnderlyingStream<C, I2, E>,
+    T::Stream: Sink<I, Error = E>,
+    C: Clone + Send + Unpin + 'static,
+    I: Send + 'static,
+    I2: Unpin,
+    E: Error + Unpin + 'static,
+{
+    type Error = ReconnectError<E>;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut *self.lock()).poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: I) -> Result<(), Self::Error> {
+        Pin::new(&mut *self.lock()).start_send(item)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut *self.lock()).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut *self.lock()).poll_close(cx)
+    }
+}
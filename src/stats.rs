@@ -0,0 +1,64 @@
+use std::time::{Duration, Instant};
+
+/// Cumulative counters of a [ReconnectStream](crate::ReconnectStream), as returned by
+/// [ReconnectStream::stats](crate::ReconnectStream::stats).
+///
+/// Durations are measured with a monotonic clock, so they are not affected by system clock adjustments.
+///
+/// This is a snapshot taken with access to the stream, not a handle which keeps updating,
+/// see [ReconnectStream::stats](crate::ReconnectStream::stats) for why.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct ReconnectStats {
+    /// Number of times an established connection was lost.
+    pub disconnects: u64,
+    /// Number of times the connection was re-established after a disconnect.
+    pub reconnects: u64,
+    /// Number of failed connection attempts, including the initial connect.
+    pub failed_attempts: u64,
+    /// Total time spent disconnected, including the ongoing outage if any.
+    pub downtime: Duration,
+    /// Number of read items dropped instead of being yielded,
+    /// because [classify_item](crate::UnderlyingStream::classify_item) returned [Drop](crate::ItemAction::Drop).
+    pub dropped_inbound: u64,
+    /// Number of items dropped because the outbound buffer was full.
+    pub dropped_outbound: u64,
+    /// Counters of the current connection, or of the last one if disconnected.
+    pub connection: ConnectionStats,
+    pub(crate) uptime: Duration,
+    pub(crate) current_downtime: Duration,
+    pub(crate) reset_at: Option<Instant>,
+}
+
+impl ReconnectStats {
+    /// Total time spent connected, including the ongoing connection if any.
+    pub fn total_uptime(&self) -> Duration {
+        self.uptime
+    }
+
+    /// Time since the connection was lost, or zero if connected or no longer reconnecting.
+    pub fn current_downtime(&self) -> Duration {
+        self.current_downtime
+    }
+
+    /// Returns the time elapsed in an ongoing interval started at `since`, not counting the part before the last reset.
+    pub(crate) fn since_reset(&self, since: Instant, now: Instant) -> Duration {
+        let since = match self.reset_at {
+            Some(reset_at) => since.max(reset_at),
+            None => since,
+        };
+        now.saturating_duration_since(since)
+    }
+}
+
+/// Counters of a single connection, which are reset whenever the connection is re-established.
+///
+/// Telling whether connections die right away or after doing useful work helps debugging flaky connections.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct ConnectionStats {
+    /// Number of items successfully handed to the underlying sink since the last connect.
+    pub items_sent_since_connect: u64,
+    /// Number of items read from the underlying stream, except the dropped ones, since the last connect.
+    pub items_received_since_connect: u64,
+}
@@ -1,6 +1,14 @@
 //! Provides the strategies used in stubborn io items
+//!
+//! This is the canonical path of the strategies, which are also re-exported as [backoff](crate::backoff).
+use log::trace;
 use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::convert::TryFrom;
+#[cfg(feature = "tokio")]
+use std::sync::{Arc, Mutex, PoisonError};
 use std::time::Duration;
+#[cfg(feature = "tokio")]
+use tokio::sync::mpsc::{error::TryRecvError, Receiver};
 
 /// Type used for defining the exponential backoff strategy.
 /// # Examples
@@ -98,8 +106,8 @@ impl Iterator for ExpBackoffIter {
     fn next(&mut self) -> Option<Self::Item> {
         let base = self.init * self.strategy.factor.powf(self.pow as f64);
         let jitter = base * self.strategy.jitter * (self.rng.gen::<f64>() * 2. - 1.);
-        let current = Duration::from_secs_f64(base + jitter);
-        self.pow += 1;
+        let current = Duration::try_from_secs_f64(base + jitter).unwrap_or(Duration::MAX);
+        self.pow = self.pow.saturating_add(1);
         match self.strategy.max {
             Some(max) => Some(max.min(current)),
             None => Some(current),
@@ -107,9 +115,308 @@ impl Iterator for ExpBackoffIter {
     }
 }
 
+/// Iterator adapter which replaces each delay of the inner iterator with a random value
+/// between zero and the delay itself ("full jitter").
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use stream_reconnect::{ReconnectOptions, strategies::{ExpBackoffStrategy, FullJitter}};
+///
+/// let options = ReconnectOptions::new().with_retries_generator(|| {
+///     FullJitter::new(
+///         ExpBackoffStrategy::new(Duration::from_secs(1), 2.0, 0.0)
+///             .with_max(Duration::from_secs(30))
+///             .into_iter(),
+///     )
+/// });
+/// ```
+pub struct FullJitter<I> {
+    inner: I,
+    rng: StdRng,
+}
+
+impl<I> FullJitter<I> {
+    pub fn new(inner: I) -> Self {
+        Self {
+            inner,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Set the seed used to generate jitter. Otherwise, will set RNG via entropy.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+}
+
+impl<I: Iterator<Item = Duration>> Iterator for FullJitter<I> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let delay = self.inner.next()?;
+        Some(delay.mul_f64(self.rng.gen::<f64>()))
+    }
+}
+
+/// Iterator adapter which keeps half of each delay of the inner iterator and replaces
+/// the other half with a random value ("equal jitter").
+pub struct EqualJitter<I> {
+    inner: I,
+    rng: StdRng,
+}
+
+impl<I> EqualJitter<I> {
+    pub fn new(inner: I) -> Self {
+        Self {
+            inner,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Set the seed used to generate jitter. Otherwise, will set RNG via entropy.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+}
+
+impl<I: Iterator<Item = Duration>> Iterator for EqualJitter<I> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let half = self.inner.next()? / 2;
+        Some(half + half.mul_f64(self.rng.gen::<f64>()))
+    }
+}
+
+/// Iterator implementing the "decorrelated jitter" backoff,
+/// where each delay is a random value between `base` and three times the previous delay, capped at `cap`.
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use stream_reconnect::{ReconnectOptions, strategies::DecorrelatedJitter};
+///
+/// let options = ReconnectOptions::new().with_retries_generator(|| {
+///     DecorrelatedJitter::new(Duration::from_secs(1), Duration::from_secs(60))
+/// });
+/// ```
+pub struct DecorrelatedJitter {
+    base: Duration,
+    cap: Duration,
+    prev: Duration,
+    rng: StdRng,
+}
+
+impl DecorrelatedJitter {
+    pub fn new(base: Duration, cap: Duration) -> Self {
+        Self {
+            base,
+            cap,
+            prev: base,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Set the seed used to generate jitter. Otherwise, will set RNG via entropy.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+}
+
+impl Iterator for DecorrelatedJitter {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let low = self.base.as_secs_f64();
+        let high = self.prev.saturating_mul(3).as_secs_f64().max(low);
+        let current = Duration::try_from_secs_f64(self.rng.gen_range(low..=high))
+            .unwrap_or(Duration::MAX)
+            .min(self.cap);
+        self.prev = current;
+        Some(current)
+    }
+}
+
+/// Iterator adapter which logs each delay pulled from the inner iterator. See [logged].
+pub struct Logged<I> {
+    inner: I,
+    name: String,
+    attempt: usize,
+}
+
+impl<I: Iterator<Item = Duration>> Iterator for Logged<I> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let delay = self.inner.next();
+        match delay {
+            Some(delay) => trace!("{}: delay #{} is {:?}", self.name, self.attempt, delay),
+            None => trace!("{}: no delay left after {}", self.name, self.attempt),
+        }
+        self.attempt += 1;
+        delay
+    }
+}
+
+/// Logs each delay pulled from `retries` at the trace level, prefixed with `name`,
+/// to see the actual schedule of a retries generator at runtime.
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use stream_reconnect::{ReconnectOptions, strategies};
+///
+/// let options = ReconnectOptions::new().with_retries_generator(|| {
+///     strategies::logged(vec![Duration::from_secs(1), Duration::from_secs(5)], "feed")
+/// });
+/// ```
+pub fn logged<I>(retries: I, name: impl Into<String>) -> Logged<I::IntoIter>
+where
+    I: IntoIterator<Item = Duration>,
+{
+    Logged {
+        inner: retries.into_iter(),
+        name: name.into(),
+        attempt: 0,
+    }
+}
+
+/// Delays pushed by an external component through a channel, as returned by [from_receiver].
+///
+/// Clones share the same channel, so that each reconnection episode pulls from it.
+#[cfg(feature = "tokio")]
+#[derive(Clone)]
+pub struct FromReceiver(Arc<Mutex<(Receiver<Duration>, Duration)>>);
+
+#[cfg(feature = "tokio")]
+impl Iterator for FromReceiver {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut guard = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        let (rx, last) = &mut *guard;
+        match rx.try_recv() {
+            Ok(delay) => {
+                *last = delay;
+                Some(delay)
+            }
+            Err(TryRecvError::Empty) => Some(*last),
+            Err(TryRecvError::Disconnected) => None,
+        }
+    }
+}
+
+/// Takes each delay from `rx`, so that an external component, such as a controller throttling
+/// the reconnections of a fleet, dictates the retry timing.
+///
+/// Each delay pushed is used for one attempt. When no new delay was pushed, the last one is reused,
+/// and attempts are made right away until the first one is received.
+/// Retries stop once all the senders are dropped and the pushed delays are used.
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use stream_reconnect::{ReconnectOptions, strategies};
+///
+/// let (tx, rx) = tokio::sync::mpsc::channel(16);
+/// tx.try_send(Duration::from_secs(5)).unwrap();
+/// let delays = strategies::from_receiver(rx);
+/// let options = ReconnectOptions::new().with_retries_generator(move || delays.clone());
+/// ```
+#[cfg(feature = "tokio")]
+pub fn from_receiver(rx: Receiver<Duration>) -> FromReceiver {
+    FromReceiver(Arc::new(Mutex::new((rx, Duration::ZERO))))
+}
+
+/// Strategies which can tell the envelope of their delays without sampling them.
+pub trait DelayBounds {
+    /// Returns the minimum and maximum delay which may be yielded for the `attempt`-th delay, starting from zero.
+    fn bounds(&self, attempt: usize) -> (Duration, Duration);
+}
+
+/// Returns the minimum and maximum delay `strategy` may yield for the `attempt`-th delay, starting from zero.
+///
+/// This allows checking that a backoff policy stays within a floor and a cap without relying on random values.
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use stream_reconnect::strategies::{self, ExpBackoffStrategy, FullJitter};
+///
+/// let strategy = FullJitter::new(
+///     ExpBackoffStrategy::new(Duration::from_secs(1), 2.0, 0.0)
+///         .with_max(Duration::from_secs(30))
+///         .into_iter(),
+/// );
+/// assert_eq!(strategies::bounds(&strategy, 2), (Duration::ZERO, Duration::from_secs(4)));
+/// assert_eq!(strategies::bounds(&strategy, 10), (Duration::ZERO, Duration::from_secs(30)));
+/// ```
+pub fn bounds<S: DelayBounds>(strategy: &S, attempt: usize) -> (Duration, Duration) {
+    strategy.bounds(attempt)
+}
+
+impl DelayBounds for ExpBackoffStrategy {
+    fn bounds(&self, attempt: usize) -> (Duration, Duration) {
+        let pow = u32::try_from(attempt).unwrap_or(u32::MAX);
+        let base = self.min.as_secs_f64() * self.factor.powf(pow as f64);
+        let bound = |secs: f64| {
+            let delay = Duration::try_from_secs_f64(secs).unwrap_or(Duration::MAX);
+            match self.max {
+                Some(max) => max.min(delay),
+                None => delay,
+            }
+        };
+        (
+            bound(base * (1. - self.jitter)),
+            bound(base * (1. + self.jitter)),
+        )
+    }
+}
+
+impl DelayBounds for ExpBackoffIter {
+    fn bounds(&self, attempt: usize) -> (Duration, Duration) {
+        self.strategy.bounds(attempt)
+    }
+}
+
+impl<I: DelayBounds> DelayBounds for FullJitter<I> {
+    fn bounds(&self, attempt: usize) -> (Duration, Duration) {
+        (Duration::ZERO, self.inner.bounds(attempt).1)
+    }
+}
+
+impl<I: DelayBounds> DelayBounds for EqualJitter<I> {
+    fn bounds(&self, attempt: usize) -> (Duration, Duration) {
+        let (min, max) = self.inner.bounds(attempt);
+        (min / 2, (max / 2).saturating_mul(2))
+    }
+}
+
+impl<I: DelayBounds> DelayBounds for Logged<I> {
+    fn bounds(&self, attempt: usize) -> (Duration, Duration) {
+        self.inner.bounds(attempt)
+    }
+}
+
+impl DelayBounds for DecorrelatedJitter {
+    fn bounds(&self, attempt: usize) -> (Duration, Duration) {
+        // every delay may triple the previous one, which starts at the base
+        let pow = u32::try_from(attempt).unwrap_or(u32::MAX).saturating_add(1);
+        let high = self
+            .base
+            .saturating_mul(3u32.saturating_pow(pow))
+            .max(self.base);
+        (self.base.min(self.cap), high.min(self.cap))
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::ExpBackoffStrategy;
+    use super::{bounds, logged, DecorrelatedJitter, EqualJitter, ExpBackoffStrategy, FullJitter};
     use std::time::Duration;
 
     #[test]
@@ -156,4 +463,146 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn test_exponential_backoff_clamps_instead_of_overflowing() {
+        let mut backoff_iter = ExpBackoffStrategy::new(Duration::from_secs(1), 2., 0.0)
+            .with_max(Duration::from_secs(30))
+            .into_iter();
+        for _ in 0..2000 {
+            assert!(backoff_iter.next().unwrap() <= Duration::from_secs(30));
+        }
+    }
+
+    #[test]
+    fn test_full_jitter_bounds() {
+        let delays = vec![Duration::from_secs(1), Duration::from_secs(10)];
+        let jittered: Vec<_> = FullJitter::new(delays.clone().into_iter())
+            .with_seed(0)
+            .collect();
+        assert_eq!(jittered.len(), delays.len());
+        for (value, delay) in jittered.into_iter().zip(delays) {
+            assert!(value <= delay, "{:?} > {:?}", value, delay);
+        }
+    }
+
+    #[test]
+    fn test_equal_jitter_bounds() {
+        let delays = vec![Duration::from_secs(1), Duration::from_secs(10)];
+        let jittered: Vec<_> = EqualJitter::new(delays.clone().into_iter())
+            .with_seed(0)
+            .collect();
+        assert_eq!(jittered.len(), delays.len());
+        for (value, delay) in jittered.into_iter().zip(delays) {
+            assert!(
+                value >= delay / 2 && value <= delay,
+                "{:?} not in [{:?}, {:?}]",
+                value,
+                delay / 2,
+                delay
+            );
+        }
+    }
+
+    #[test]
+    fn test_logged_yields_the_inner_delays() {
+        let delays = vec![Duration::from_secs(1), Duration::from_secs(2)];
+        assert_eq!(logged(delays.clone(), "test").collect::<Vec<_>>(), delays);
+
+        let strategy = ExpBackoffStrategy::new(Duration::from_secs(1), 2., 0.).into_iter();
+        assert_eq!(
+            bounds(&logged(strategy, "test"), 3),
+            (Duration::from_secs(8), Duration::from_secs(8))
+        );
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn test_from_receiver_takes_the_pushed_delays() {
+        let (tx, rx) = tokio::sync::mpsc::channel(4);
+        let mut delays = super::from_receiver(rx);
+        assert_eq!(delays.next(), Some(Duration::ZERO));
+        tx.try_send(Duration::from_secs(1)).unwrap();
+        tx.try_send(Duration::from_secs(2)).unwrap();
+        assert_eq!(delays.clone().next(), Some(Duration::from_secs(1)));
+        assert_eq!(delays.next(), Some(Duration::from_secs(2)));
+        assert_eq!(delays.next(), Some(Duration::from_secs(2)));
+        drop(tx);
+        assert_eq!(delays.next(), None);
+    }
+
+    #[test]
+    fn test_jitter_is_reproducible_with_seed() {
+        let delays = || vec![Duration::from_secs(4); 5].into_iter();
+        let a: Vec<_> = FullJitter::new(delays()).with_seed(42).collect();
+        let b: Vec<_> = FullJitter::new(delays()).with_seed(42).collect();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_decorrelated_jitter_bounds() {
+        let base = Duration::from_secs(1);
+        let cap = Duration::from_secs(20);
+        let mut prev = base;
+        for value in DecorrelatedJitter::new(base, cap).with_seed(0).take(20) {
+            assert!(value >= base, "{:?} < {:?}", value, base);
+            assert!(value <= cap, "{:?} > {:?}", value, cap);
+            assert!(value <= prev * 3, "{:?} > 3 * {:?}", value, prev);
+            prev = value;
+        }
+    }
+
+    #[test]
+    fn test_sampled_delays_are_within_bounds() {
+        let strategy = || {
+            ExpBackoffStrategy::new(Duration::from_secs(1), 2., 0.1)
+                .with_max(Duration::from_secs(60))
+                .into_iter()
+        };
+        let full = FullJitter::new(strategy()).with_seed(0);
+        let equal = EqualJitter::new(strategy()).with_seed(0);
+        let decorrelated = DecorrelatedJitter::new(Duration::from_secs(1), Duration::from_secs(60));
+        assert_eq!(
+            bounds(&strategy(), 0),
+            (Duration::from_millis(900), Duration::from_millis(1100))
+        );
+        assert_eq!(bounds(&strategy(), 20).1, Duration::from_secs(60));
+        assert_eq!(bounds(&decorrelated, 0).1, Duration::from_secs(3));
+
+        let check = |attempt: usize, value: Duration, (min, max): (Duration, Duration)| {
+            assert!(
+                value >= min && value <= max,
+                "attempt {}: {:?} not in [{:?}, {:?}]",
+                attempt,
+                value,
+                min,
+                max
+            );
+        };
+        for (attempt, value) in strategy().take(10).enumerate() {
+            check(attempt, value, bounds(&strategy(), attempt));
+        }
+        for (attempt, value) in FullJitter::new(strategy())
+            .with_seed(0)
+            .take(10)
+            .enumerate()
+        {
+            check(attempt, value, bounds(&full, attempt));
+        }
+        for (attempt, value) in EqualJitter::new(strategy())
+            .with_seed(0)
+            .take(10)
+            .enumerate()
+        {
+            check(attempt, value, bounds(&equal, attempt));
+        }
+        for (attempt, value) in
+            DecorrelatedJitter::new(Duration::from_secs(1), Duration::from_secs(60))
+                .with_seed(0)
+                .take(10)
+                .enumerate()
+        {
+            check(attempt, value, bounds(&decorrelated, attempt));
+        }
+    }
 }
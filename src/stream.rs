@@ -1,17 +1,35 @@
+use std::any::Any;
+use std::collections::VecDeque;
 use std::error::Error;
+use std::fmt;
 use std::future::Future;
-use std::iter::once;
 use std::marker::PhantomData;
-use std::ops::{Deref, DerefMut};
 use std::pin::Pin;
-use std::task::{Context, Poll};
-use std::time::Duration;
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
 
-use futures::future::BoxFuture;
+use futures::channel::mpsc;
+use futures::future::{self, BoxFuture, Either};
 use futures::{ready, FutureExt, Sink, Stream};
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
+#[cfg(feature = "tokio")]
+use tokio::sync::watch;
+#[cfg(feature = "tracing")]
+use tracing::Instrument;
 
-use crate::config::ReconnectOptions;
+use crate::cancel::{CancelOnDrop, CancelToken};
+use crate::clock::SharedClock;
+#[cfg(feature = "test-util")]
+use crate::clock::TestClock;
+use crate::config::{
+    Callback, ControlFlow, CtorArgRefresh, EndpointPolicy, InitialConnectSendPolicy,
+    OutboundOverflowPolicy, ReconnectContext, ReconnectOptions, ReconnectSummary, Retries,
+    ShutdownSignal,
+};
+use crate::error::ReconnectError;
+use crate::runtime;
+use crate::stats::{ConnectionStats, ReconnectStats};
 
 /// Trait that should be implemented for an [Stream] and/or [Sink]
 /// item to enable it to work with the [ReconnectStream] struct.
@@ -24,17 +42,52 @@ where
 
     /// The creation function is used by [ReconnectStream] in order to establish both the initial IO connection
     /// in addition to performing reconnects.
+    ///
+    /// When reconnecting, the returned future is polled from the [Stream]/[Sink] methods of the
+    /// [ReconnectStream], which return `Pending` until it completes, so the task is free to
+    /// run other work in the meantime. Blocking work should be offloaded to a blocking thread pool.
     #[cfg(feature = "not-send")]
     fn establish(ctor_arg: C) -> impl Future<Output = Result<Self::Stream, E>>;
 
     /// The creation function is used by [ReconnectStream] in order to establish both the initial IO connection
     /// in addition to performing reconnects.
+    ///
+    /// When reconnecting, the returned future is polled from the [Stream]/[Sink] methods of the
+    /// [ReconnectStream], which return `Pending` until it completes, so the task is free to
+    /// run other work in the meantime. Blocking work should be offloaded to a blocking thread pool.
     #[cfg(not(feature = "not-send"))]
     fn establish(ctor_arg: C) -> impl Future<Output = Result<Self::Stream, E>> + Send;
 
+    /// Like [establish](Self::establish), with a token which is cancelled if the attempt is abandoned
+    /// while in flight, for transports which need to tear down more than what dropping the future does.
+    /// [ReconnectStream] always establishes connections through this method.
+    /// By default, the token is ignored and [establish](Self::establish) is called.
+    #[cfg(feature = "not-send")]
+    #[allow(unused_variables)]
+    fn establish_with_cancel(
+        ctor_arg: C,
+        cancel: CancelToken,
+    ) -> impl Future<Output = Result<Self::Stream, E>> {
+        Self::establish(ctor_arg)
+    }
+
+    /// Like [establish](Self::establish), with a token which is cancelled if the attempt is abandoned
+    /// while in flight, for transports which need to tear down more than what dropping the future does.
+    /// [ReconnectStream] always establishes connections through this method.
+    /// By default, the token is ignored and [establish](Self::establish) is called.
+    #[cfg(not(feature = "not-send"))]
+    #[allow(unused_variables)]
+    fn establish_with_cancel(
+        ctor_arg: C,
+        cancel: CancelToken,
+    ) -> impl Future<Output = Result<Self::Stream, E>> + Send {
+        Self::establish(ctor_arg)
+    }
+
     /// When sink send experience an `Error` during operation, it does not necessarily mean
     /// it is a disconnect/termination (ex: WouldBlock).
-    /// You may specify which errors are considered "disconnects" by this method.
+    /// You may specify which errors are considered "disconnects" by this method,
+    /// which can delegate to [is_io_disconnect] for io errors.
     fn is_write_disconnect_error(err: &E) -> bool;
 
     /// It's common practice for [Stream] implementations that return an `Err`
@@ -46,13 +99,372 @@ where
         false
     }
 
-    /// This is returned when retry quota exhausted.
-    fn exhaust_err() -> E;
+    /// Decides what to do with a read item which is not considered a "disconnect" by
+    /// [is_read_disconnect_error](Self::is_read_disconnect_error).
+    /// This allows protocols signalling a shutdown with an in-band message to reconnect
+    /// without leaking the control message to the consumer.
+    /// By default, every item is yielded.
+    fn classify_item(item: I) -> ItemAction<I> {
+        ItemAction::Yield(item)
+    }
+
+    /// Extracts the error from a read item considered a "disconnect" by
+    /// [is_read_disconnect_error](Self::is_read_disconnect_error), so that it can be passed to
+    /// [ReconnectOptions::with_retries_generator_from_error].
+    /// By default, read disconnects carry no error.
+    #[allow(unused_variables)]
+    fn read_disconnect_error(item: &I) -> Option<&E> {
+        None
+    }
+
+    /// Tells whether an error returned by [establish](Self::establish) means that no attempt will ever succeed,
+    /// such as an authentication rejection, unlike a transient failure such as a DNS resolution failure.
+    /// Upon a fatal error, the stream gives up right away instead of retrying.
+    /// By default, no error is considered fatal.
+    #[allow(unused_variables)]
+    fn is_fatal_establish_error(err: &E) -> bool {
+        false
+    }
+
+    /// Tells whether the end of `stream` is a legitimate end of the stream, such as a server
+    /// which is done sending, unlike a connection closed abruptly.
+    /// Upon a clean EOF, the [ReconnectStream] ends for good instead of reconnecting.
+    /// By default, the end of the underlying stream is considered a disconnect.
+    #[allow(unused_variables)]
+    fn is_clean_eof(stream: &Self::Stream) -> bool {
+        false
+    }
+
+    /// This used to be returned when the retries were exhausted.
+    /// The [ReconnectStream] now reports [ReconnectsExhausted](ReconnectError::ReconnectsExhausted) instead,
+    /// and never calls this; it is only kept so that existing implementations still compile.
+    ///
+    /// # Panics
+    ///
+    /// The default implementation panics, as there is no error of type `E` to build.
+    #[deprecated(note = "the stream reports ReconnectError::ReconnectsExhausted instead")]
+    fn exhaust_err() -> E {
+        unimplemented!("exhaust_err is no longer used, see ReconnectError::ReconnectsExhausted")
+    }
+
+    /// Decides whether a WebSocket close frame received from the peer should trigger a reconnect,
+    /// or end the stream for good. Close frames are only inspected if
+    /// [classify_item](Self::classify_item) delegates to [classify_close](crate::websocket::classify_close).
+    /// By default, a normal close (1000) ends the stream and any other close code triggers a reconnect.
+    #[cfg(feature = "tungstenite")]
+    fn should_reconnect_on_close(frame: &tungstenite::protocol::CloseFrame<'_>) -> bool {
+        frame.code != tungstenite::protocol::frame::coding::CloseCode::Normal
+    }
+}
+
+/// Tells whether an [io::Error](std::io::Error) means that the connection is lost, going by its kind:
+/// [ConnectionReset](std::io::ErrorKind::ConnectionReset), [ConnectionAborted](std::io::ErrorKind::ConnectionAborted),
+/// [BrokenPipe](std::io::ErrorKind::BrokenPipe), [NotConnected](std::io::ErrorKind::NotConnected)
+/// and [UnexpectedEof](std::io::ErrorKind::UnexpectedEof).
+///
+/// Implementations of [UnderlyingStream] over io based transports can delegate
+/// [is_write_disconnect_error](UnderlyingStream::is_write_disconnect_error) to it.
+/// # Examples
+///
+/// ```
+/// use std::io;
+/// use stream_reconnect::is_io_disconnect;
+///
+/// assert!(is_io_disconnect(&io::Error::from(io::ErrorKind::BrokenPipe)));
+/// assert!(!is_io_disconnect(&io::Error::from(io::ErrorKind::WouldBlock)));
+/// ```
+pub fn is_io_disconnect(err: &std::io::Error) -> bool {
+    use std::io::ErrorKind::*;
+
+    matches!(
+        err.kind(),
+        ConnectionReset | ConnectionAborted | BrokenPipe | NotConnected | UnexpectedEof
+    )
+}
+
+/// What to do with a read item, as decided by [UnderlyingStream::classify_item].
+#[derive(Debug)]
+pub enum ItemAction<I> {
+    /// Yield the item to the consumer.
+    Yield(I),
+    /// Drop the item and treat the connection as lost.
+    Disconnect,
+    /// Drop the item and keep reading.
+    Drop,
+    /// Drop the item and end the stream without reconnecting, as when the peer closed the connection on purpose.
+    /// The stream is [shut down](ConnectionStatus::Shutdown) just like when it is closed.
+    Terminate,
+}
+
+/// The state of a [ReconnectStream]'s connection, as reported by [ReconnectStream::status].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionStatus {
+    /// The underlying stream is connected.
+    Connected,
+    /// The connection is lost and the stream is trying to re-establish it.
+    /// `attempt` is the number of retries consumed from the current retries iterator.
+    Reconnecting { attempt: usize },
+    /// The connection is lost and no reconnect attempt is scheduled yet.
+    Disconnected,
+    /// Too many reconnect attempts failed in a row and sends are rejected until a probe attempt succeeds,
+    /// see [ReconnectOptions::with_circuit_breaker].
+    CircuitOpen { attempt: usize },
+    /// All reconnect attempts have failed. The stream will not reconnect anymore.
+    Dead,
+    /// The shutdown signal completed while disconnected, or the sink was closed.
+    /// The stream will not reconnect anymore.
+    Shutdown,
+}
+
+/// A transition of a [ReconnectStream]'s connection, as yielded by [ReconnectStream::events].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConnEvent {
+    /// The connection was re-established.
+    Connected,
+    /// The connection was lost.
+    Disconnected,
+    /// A reconnect attempt failed.
+    ReconnectFailed,
+    /// The stream gave up reconnecting.
+    Dead,
+}
+
+/// What caused the last disconnect, as reported by [ReconnectStream::last_disconnect_kind].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DisconnectKind {
+    /// A read returned a disconnect error, a [disconnect item](ItemAction::Disconnect), or the end of the stream.
+    Read,
+    /// A write, flush or close returned a disconnect error.
+    Write,
+    /// Nothing was read within the [idle timeout](crate::ReconnectOptions::with_idle_timeout).
+    IdleTimeout,
+    /// A flush did not complete within the [flush timeout](crate::ReconnectOptions::with_flush_timeout).
+    FlushTimeout,
+    /// The connection was dropped on purpose, by [force_reconnect](ReconnectStream::force_reconnect)
+    /// or because the [paired](crate::Paired) stream disconnected.
+    Forced,
+}
+
+/// Why a connect attempt failed.
+enum ConnectFailure<E> {
+    Error(E),
+    TimedOut,
+    #[cfg_attr(not(feature = "catch-panic"), allow(dead_code))]
+    Panicked(String),
+}
+
+impl<E> ConnectFailure<E> {
+    fn error(&self) -> Option<&E> {
+        match self {
+            ConnectFailure::Error(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    fn into_error(self) -> ReconnectError<E> {
+        match self {
+            ConnectFailure::Error(err) => ReconnectError::Underlying(err),
+            ConnectFailure::TimedOut => ReconnectError::ConnectTimeout,
+            ConnectFailure::Panicked(message) => ReconnectError::EstablishPanic(message),
+        }
+    }
 }
 
 struct AttemptsTracker {
     attempt_num: usize,
-    retries_remaining: Box<dyn Iterator<Item = Duration> + Send>,
+    retries_remaining: Retries,
+    /// A delay pulled ahead of time from the retries iterator, to tell whether it is exhausted
+    peeked: Option<Duration>,
+    /// Whether the near exhaustion callback fired since the last successful connect
+    near_exhaustion_reported: bool,
+}
+
+impl AttemptsTracker {
+    fn new(retries_remaining: Retries) -> Self {
+        AttemptsTracker {
+            attempt_num: 0,
+            retries_remaining,
+            peeked: None,
+            near_exhaustion_reported: false,
+        }
+    }
+
+    /// Pulls the next delay of the retries, the peeked one first.
+    fn next_retry(&mut self) -> Option<Duration> {
+        match self.peeked.take() {
+            Some(delay) => Some(delay),
+            None => self.retries_remaining.next(self.attempt_num),
+        }
+    }
+
+    /// Returns how many delays the retries iterator has left, if it can be told.
+    /// This is exact if the iterator tells its size, otherwise only running out of delays can be told,
+    /// by peeking at the next one.
+    fn retries_left(&mut self) -> Option<usize> {
+        let retries = match &mut self.retries_remaining {
+            Retries::Iter(retries) => retries,
+            Retries::Backoff(_) => return None,
+        };
+        let peeked = usize::from(self.peeked.is_some());
+        match retries.size_hint() {
+            (lower, Some(upper)) if lower == upper => Some(lower + peeked),
+            _ if peeked > 0 => None,
+            _ => {
+                self.peeked = retries.next();
+                self.peeked.is_none().then_some(0)
+            }
+        }
+    }
+
+    /// Warns the near exhaustion callback once per reconnection episode,
+    /// when the retries iterator has at most its threshold of delays left.
+    fn report_near_exhaustion(&mut self, options: &ReconnectOptions) {
+        let (threshold, callback) = match options.on_near_exhaustion() {
+            Some(on_near_exhaustion) if !self.near_exhaustion_reported => on_near_exhaustion,
+            _ => return,
+        };
+        match self.retries_left() {
+            Some(left) if left <= *threshold => {
+                self.near_exhaustion_reported = true;
+                warn!("Only {} reconnect delays left.", left);
+                callback(left);
+            }
+            _ => {}
+        }
+    }
+
+    fn should_continue(&self, options: &ReconnectOptions, elapsed: Duration) -> bool {
+        match options.should_continue() {
+            Some(should_continue) if !should_continue(self.attempt_num, elapsed) => {
+                error!(
+                    "Giving up as requested after {} attempts.",
+                    self.attempt_num
+                );
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// Pulls the delay before the next attempt and counts it as consumed.
+    /// `elapsed` is the time spent in the current reconnection episode so far.
+    fn next_delay(&mut self, options: &ReconnectOptions, elapsed: Duration) -> Option<Duration> {
+        if !self.should_continue(options, elapsed) {
+            return None;
+        }
+        if let Some(max) = options.max_attempts() {
+            if self.attempt_num >= max {
+                error!("Maximum of {} reconnect attempts reached.", max);
+                return None;
+            }
+        }
+        let mut delay = self.next_retry()?;
+        if let Some(min) = options.min_delay() {
+            delay = delay.max(min);
+        }
+        if self.attempt_num == 0 {
+            delay = delay.saturating_add(options.initial_jitter());
+        }
+        if delay > options.max_delay() {
+            warn!(
+                "Delay of {:?} is capped to {:?}.",
+                delay,
+                options.max_delay()
+            );
+            delay = options.max_delay();
+        }
+        if let Some(max) = options.max_reconnect_duration() {
+            if elapsed.saturating_add(delay) > max {
+                error!("Reconnect duration budget of {:?} exhausted.", max);
+                return None;
+            }
+        }
+        self.attempt_num += 1;
+        self.report_near_exhaustion(options);
+        Some(delay)
+    }
+
+    /// Schedules a probe attempt of an open circuit after `cooldown`.
+    fn next_probe(
+        &mut self,
+        options: &ReconnectOptions,
+        elapsed: Duration,
+        cooldown: Duration,
+    ) -> Option<Duration> {
+        if !self.should_continue(options, elapsed) {
+            return None;
+        }
+        if let Some(max) = options.max_reconnect_duration() {
+            if elapsed.saturating_add(cooldown) > max {
+                error!("Reconnect duration budget of {:?} exhausted.", max);
+                return None;
+            }
+        }
+        self.attempt_num += 1;
+        Some(cooldown)
+    }
+}
+
+/// The start times of the recent connect attempts, to enforce the
+/// [attempt rate limit](ReconnectOptions::with_attempt_rate_limit).
+#[derive(Default)]
+struct AttemptRateLimiter {
+    starts: VecDeque<Instant>,
+}
+
+impl AttemptRateLimiter {
+    /// Records an attempt meant to start after `delay`, postponing it if needed,
+    /// also to wait for the [shared limiter](ReconnectOptions::with_reconnect_limiter) if any.
+    /// Returns the delay to wait before the attempt.
+    fn schedule(&mut self, options: &ReconnectOptions, delay: Duration) -> Duration {
+        let now = options.clock().now();
+        let start = now + self.limit(options, now, delay);
+        let limiter = match options.reconnect_limiter() {
+            Some(limiter) => limiter,
+            None => return start - now,
+        };
+        let granted = limiter.reserve(start);
+        if granted > start {
+            debug!(
+                "Shared reconnect budget exhausted, postponing the attempt by {:?}.",
+                granted - start
+            );
+            if let Some(last) = self.starts.back_mut() {
+                *last = granted;
+            }
+        }
+        granted - now
+    }
+
+    /// Records an attempt meant to start after `delay` since `now`, postponing it
+    /// if the attempt rate limit of this stream is reached. Returns the delay to wait before the attempt.
+    fn limit(&mut self, options: &ReconnectOptions, now: Instant, delay: Duration) -> Duration {
+        let (max, per) = match options.attempt_rate_limit() {
+            Some((max, per)) if max > 0 => (max, per),
+            _ => return delay,
+        };
+        let mut start = now + delay;
+        while let Some(&oldest) = self.starts.front() {
+            if oldest + per <= start {
+                self.starts.pop_front();
+            } else if self.starts.len() >= max {
+                start = oldest + per;
+            } else {
+                break;
+            }
+        }
+        self.starts.push_back(start);
+        let postponed = start - now;
+        if postponed > delay {
+            debug!(
+                "Attempt rate limit reached, postponing the attempt by {:?}.",
+                postponed - delay
+            );
+        }
+        postponed
+    }
 }
 
 struct ReconnectStatus<T, C, I, E>
@@ -62,10 +474,22 @@ where
     E: Error,
 {
     attempts_tracker: AttemptsTracker,
+    disconnected_at: Instant,
+    /// The delay pulled for the scheduled attempt and when its sleep started.
+    scheduled_delay: Option<(Duration, Instant)>,
+    /// Set when the options were replaced, so that the retries are regenerated at the next attempt.
+    retries_outdated: bool,
+    /// Consecutive failed attempts in this episode.
+    failures: usize,
+    circuit_open: bool,
+    /// The error of the last failed attempt, for the give up callback
+    last_error: Option<Arc<ReconnectError<E>>>,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
     #[cfg(not(feature = "not-send"))]
-    reconnect_attempt: BoxFuture<'static, Result<T::Stream, E>>,
+    reconnect_attempt: BoxFuture<'static, Result<T::Stream, ConnectFailure<E>>>,
     #[cfg(feature = "not-send")]
-    reconnect_attempt: LocalBoxFuture<'static, Result<T::Stream, E>>,
+    reconnect_attempt: LocalBoxFuture<'static, Result<T::Stream, ConnectFailure<E>>>,
     _marker: PhantomData<(C, I, E)>,
 }
 
@@ -73,23 +497,82 @@ impl<T, C, I, E> ReconnectStatus<T, C, I, E>
 where
     T: UnderlyingStream<C, I, E>,
     C: Clone + Send + Unpin + 'static,
-    E: Error + Unpin,
+    E: Error + Unpin + 'static,
 {
-    pub fn new(options: &ReconnectOptions) -> Self {
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub fn new(options: &ReconnectOptions, attempts_tracker: AttemptsTracker) -> Self {
         ReconnectStatus {
-            attempts_tracker: AttemptsTracker {
-                attempt_num: 0,
-                retries_remaining: (options.retries_to_attempt_fn())(),
-            },
+            attempts_tracker,
+            disconnected_at: options.clock().now(),
+            scheduled_delay: None,
+            retries_outdated: false,
+            failures: 0,
+            circuit_open: false,
+            last_error: None,
+            #[cfg(feature = "tracing")]
+            span: tracing::info_span!(
+                "reconnect",
+                name = options.name().unwrap_or_default(),
+                attempt = tracing::field::Empty,
+                delay = tracing::field::Empty,
+            ),
             reconnect_attempt: async { unreachable!("Not going to happen") }.boxed(),
             _marker: PhantomData,
         }
     }
 }
 
+/// Extracts the message of a caught panic.
+#[cfg(feature = "catch-panic")]
+fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    match panic.downcast::<String>() {
+        Ok(message) => *message,
+        Err(panic) => match panic.downcast_ref::<&str>() {
+            Some(message) => message.to_string(),
+            None => "establish panicked".to_string(),
+        },
+    }
+}
+
+fn register(slot: &mut Option<Waker>, cx: &Context) {
+    match slot {
+        Some(waker) if waker.will_wake(cx.waker()) => {}
+        _ => *slot = Some(cx.waker().clone()),
+    }
+}
+
+/// The ctor args to establish connections with, and the one in use.
+struct Endpoints<C> {
+    ctor_args: Vec<C>,
+    current: usize,
+}
+
+impl<C: Clone> Endpoints<C> {
+    fn current(&self) -> C {
+        self.ctor_args[self.current].clone()
+    }
+
+    /// Picks the ctor arg for the next attempt.
+    /// `episode_start` is true for the first attempt after the connection was lost.
+    fn select(&mut self, policy: EndpointPolicy, episode_start: bool) -> C {
+        self.current = match (policy, episode_start) {
+            (EndpointPolicy::Failover, true) => 0,
+            _ => (self.current + 1) % self.ctor_args.len(),
+        };
+        self.current()
+    }
+}
+
 /// The ReconnectStream is a wrapper over a [Stream]/[Sink] item that will automatically
 /// invoke the [UnderlyingStream::establish] upon initialization and when a reconnect is needed.
-/// Because it implements deref, you are able to invoke all of the original methods on the wrapped stream.
+/// The wrapped stream is reachable through [get_ref](Self::get_ref) and [get_mut](Self::get_mut) while connected.
+///
+/// # Sink item bounds
+///
+/// Items sent through the [Sink] only need to be `Send + 'static`, since they are moved into the underlying sink,
+/// or into the [outbound buffer](ReconnectOptions::with_outbound_buffer) while disconnected.
+/// They are never cloned, unless [with_resend_unflushed](ReconnectOptions::with_resend_unflushed) is enabled,
+/// which is the only option requiring them to be `Clone`, since it keeps a copy of each item until it is flushed.
 pub struct ReconnectStream<T, C, I, E>
 where
     T: UnderlyingStream<C, I, E>,
@@ -97,9 +580,43 @@ where
     E: Error,
 {
     status: Status<T, C, I, E>,
-    stream: T::Stream,
+    stream: Option<T::Stream>,
     options: ReconnectOptions,
-    ctor_arg: C,
+    endpoints: Endpoints<C>,
+    /// The wakers of the last read and write polls, both woken when the connection state changes,
+    /// so that the two halves of a split stream go through the same disconnect and reconnect.
+    read_waker: Option<Waker>,
+    write_waker: Option<Waker>,
+    inbound: VecDeque<I>,
+    outbound: VecDeque<Box<dyn Any + Send>>,
+    /// Number of items of the outbound buffer which were sent and are yet to be flushed
+    drained_unflushed: usize,
+    unflushed: VecDeque<Box<dyn Any + Send>>,
+    stats: ReconnectStats,
+    connected_at: Instant,
+    last_connected_at: Option<Instant>,
+    last_disconnected_at: Option<Instant>,
+    last_disconnect_kind: Option<DisconnectKind>,
+    last_read_at: Instant,
+    /// Consecutive read items considered a disconnect, tolerated up to the disconnect error threshold
+    read_errors: usize,
+    idle_timer: Option<BoxFuture<'static, ()>>,
+    /// Runs while a flush of the underlying sink is pending
+    flush_timer: Option<BoxFuture<'static, ()>>,
+    /// The future of the async on_connect callback, which holds back reads until it completes
+    on_connect_task: Option<BoxFuture<'static, ()>>,
+    shutdown_signal: Option<ShutdownSignal>,
+    paused: bool,
+    /// The attempts and failures of the last reconnection episode and when it succeeded,
+    /// kept to continue the backoff if the connection is not stable
+    previous_attempts: Option<(AttemptsTracker, usize, Instant)>,
+    rate_limiter: AttemptRateLimiter,
+    events: Vec<mpsc::UnboundedSender<ConnEvent>>,
+    attempts: Vec<mpsc::UnboundedSender<Result<(), Arc<ReconnectError<E>>>>>,
+    #[cfg(feature = "tokio")]
+    options_watch: Option<watch::Receiver<ReconnectOptions>>,
+    #[cfg(feature = "tokio")]
+    status_watch: watch::Sender<ConnectionStatus>,
 }
 
 enum Status<T, C, I, E>
@@ -110,30 +627,27 @@ where
 {
     Connected,
     Disconnected(ReconnectStatus<T, C, I, E>),
+    Paused { disconnected_at: Instant },
     FailedAndExhausted, // the way one feels after programming in dynamically typed languages
+    Shutdown,
 }
 
-impl<T, C, I, E> Deref for ReconnectStream<T, C, I, E>
+impl<T, C, I, E> fmt::Debug for ReconnectStream<T, C, I, E>
 where
     T: UnderlyingStream<C, I, E>,
-    C: Clone + Send + Unpin,
-    E: Error,
-{
-    type Target = T::Stream;
-
-    fn deref(&self) -> &Self::Target {
-        &self.stream
-    }
-}
-
-impl<T, C, I, E> DerefMut for ReconnectStream<T, C, I, E>
-where
-    T: UnderlyingStream<C, I, E>,
-    C: Clone + Send + Unpin,
-    E: Error,
+    C: Clone + Send + Unpin + 'static,
+    I: Unpin,
+    E: Error + Unpin + 'static,
 {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.stream
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let attempt = match &self.status {
+            Status::Disconnected(status) => status.attempts_tracker.attempt_num,
+            _ => 0,
+        };
+        f.debug_struct("ReconnectStream")
+            .field("status", &self.status())
+            .field("attempt", &attempt)
+            .finish_non_exhaustive()
     }
 }
 
@@ -142,156 +656,1463 @@ where
     T: UnderlyingStream<C, I, E>,
     C: Clone + Send + Unpin + 'static,
     I: Unpin,
-    E: Error + Unpin,
+    E: Error + Unpin + 'static,
 {
+    /// Returns the current state of the connection.
+    pub fn status(&self) -> ConnectionStatus {
+        match &self.status {
+            Status::Connected => ConnectionStatus::Connected,
+            Status::Disconnected(status) => match status.attempts_tracker.attempt_num {
+                attempt if status.circuit_open => ConnectionStatus::CircuitOpen { attempt },
+                0 => ConnectionStatus::Disconnected,
+                attempt => ConnectionStatus::Reconnecting { attempt },
+            },
+            Status::Paused { .. } => ConnectionStatus::Disconnected,
+            Status::FailedAndExhausted => ConnectionStatus::Dead,
+            Status::Shutdown => ConnectionStatus::Shutdown,
+        }
+    }
+
+    /// Returns true if the stream gave up reconnecting because the retries were exhausted.
+    ///
+    /// Once dead, the [Stream] ends just like when the underlying stream ends normally,
+    /// so this tells a give-up apart from a clean end of stream.
+    pub fn is_dead(&self) -> bool {
+        matches!(self.status, Status::FailedAndExhausted)
+    }
+
+    /// Returns false once the stream will never connect again, i.e. when it is [dead](Self::is_dead)
+    /// or [shut down](ConnectionStatus::Shutdown), which tells whether a send may ever succeed
+    /// without matching on the [status](Self::status).
+    ///
+    /// Pausing reconnection does not disable it, as it can be resumed.
+    pub fn reconnection_enabled(&self) -> bool {
+        !matches!(self.status, Status::FailedAndExhausted | Status::Shutdown)
+    }
+
+    /// Returns a reference to the underlying stream while connected, `None` otherwise,
+    /// for instance to read the peer address of a socket.
+    ///
+    /// The underlying stream is replaced on each reconnect, so the reference cannot be held across one.
+    pub fn get_ref(&self) -> Option<&T::Stream> {
+        match self.status {
+            Status::Connected => self.stream.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the underlying stream while connected, `None` otherwise.
+    ///
+    /// Reading from or writing to it directly bypasses the disconnect detection and the buffers of this stream,
+    /// so it is best kept to adjusting settings of the connection.
+    pub fn get_mut(&mut self) -> Option<&mut T::Stream> {
+        match self.status {
+            Status::Connected => self.stream.as_mut(),
+            _ => None,
+        }
+    }
+
+    /// Resolves once the stream is connected, driving the reconnection meanwhile.
+    /// Fails if the stream gave up reconnecting, with
+    /// [ReconnectsExhausted](ReconnectError::ReconnectsExhausted) or [Shutdown](ReconnectError::Shutdown).
+    ///
+    /// While [paused](Self::pause), this waits until reconnecting is resumed.
+    pub fn wait_connected(&mut self) -> impl Future<Output = Result<(), ReconnectError<E>>> + '_ {
+        future::poll_fn(move |cx| {
+            self.register_write_waker(cx);
+            self.poll_reconnect(cx);
+            let result = match self.status {
+                Status::Connected => Ok(()),
+                Status::Disconnected(_) | Status::Paused { .. } => return Poll::Pending,
+                Status::FailedAndExhausted => Err(ReconnectError::ReconnectsExhausted),
+                Status::Shutdown => Err(ReconnectError::Shutdown),
+            };
+            Poll::Ready(result.map_err(|err| err.named(self.options.name())))
+        })
+    }
+
+    /// Returns a [Stream] of the connection transitions from now on, the ones the callbacks are invoked for.
+    /// This allows reacting to them in a select loop instead of from within callbacks.
+    ///
+    /// Events are sent while this stream is polled, and the events stream ends once this stream is dropped.
+    /// Events are kept until consumed, so the events stream should be polled or dropped.
+    pub fn events(&mut self) -> impl Stream<Item = ConnEvent> {
+        let (tx, rx) = mpsc::unbounded();
+        self.events.push(tx);
+        rx
+    }
+
+    fn emit(&mut self, event: ConnEvent) {
+        self.publish_status();
+        self.events.retain(|tx| tx.unbounded_send(event).is_ok());
+    }
+
+    /// Resolves the next time the [status](Self::status) changes, with the new status,
+    /// like [watch::Receiver::changed] combined with a borrow of the new value.
+    /// The future does not borrow this stream, so that it can be awaited in a select loop driving the stream.
+    ///
+    /// The status only changes while this stream is polled, or when it is paused, resumed or reconnected by hand.
+    /// If this stream is dropped, the future resolves with the last status.
+    #[cfg(feature = "tokio")]
+    pub fn changed(&self) -> impl Future<Output = ConnectionStatus> {
+        let mut status = self.status_watch.subscribe();
+        async move {
+            // the status does not change anymore once the stream is dropped
+            let _ = status.changed().await;
+            let current = *status.borrow();
+            current
+        }
+    }
+
+    /// Returns a [Stream] of the outcomes of the reconnect attempts from now on:
+    /// `Ok` when an attempt established a connection, or the error it failed with.
+    /// The initial connect attempts are made before the stream exists, so they are not observed.
+    ///
+    /// The errors are shared between the attempts streams, hence the [Arc].
+    /// Like the [events](Self::events), the outcomes are sent while this stream is polled
+    /// and kept until consumed, and the attempts stream ends once this stream is dropped.
+    pub fn attempts(&mut self) -> impl Stream<Item = Result<(), Arc<ReconnectError<E>>>> {
+        let (tx, rx) = mpsc::unbounded();
+        self.attempts.push(tx);
+        rx
+    }
+
+    fn emit_attempt(&mut self, outcome: Result<(), &Arc<ReconnectError<E>>>) {
+        self.attempts
+            .retain(|tx| tx.unbounded_send(outcome.map_err(Arc::clone)).is_ok());
+    }
+
+    /// Takes the items left in the outbound buffer once the stream is dead or shut down,
+    /// so they can be persisted or routed elsewhere instead of being lost.
+    /// This includes the sent items which were not confirmed flushed if they are resent on reconnect.
+    /// Returns an empty `Vec` while the stream may still reconnect and send them.
+    ///
+    /// Only the items of type `X` are taken, so that items of another [Sink] item type are kept.
+    pub fn take_pending<X: 'static>(&mut self) -> Vec<X> {
+        if !matches!(self.status, Status::FailedAndExhausted | Status::Shutdown) {
+            return Vec::new();
+        }
+        let mut pending = Vec::new();
+        for item in std::mem::take(&mut self.outbound) {
+            match item.downcast::<X>() {
+                Ok(item) => pending.push(*item),
+                Err(item) => self.outbound.push_back(item),
+            }
+        }
+        pending
+    }
+
+    /// Returns the time left before the next reconnect attempt starts,
+    /// which is zero while the attempt is in progress.
+    /// Returns `None` when connected, or when no reconnect attempt is scheduled.
+    pub fn next_retry_in(&self) -> Option<Duration> {
+        match &self.status {
+            Status::Disconnected(status) => status.scheduled_delay.map(|(delay, started_at)| {
+                delay.saturating_sub(self.options.clock().elapsed(started_at))
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns when the connection was last established, or `None` if it never was.
+    pub fn last_connected_at(&self) -> Option<Instant> {
+        self.last_connected_at
+    }
+
+    /// Returns when the connection was last lost, or `None` if it never was.
+    pub fn last_disconnected_at(&self) -> Option<Instant> {
+        self.last_disconnected_at
+    }
+
+    /// Returns what caused the connection to be last lost, or `None` if it never was.
+    pub fn last_disconnect_kind(&self) -> Option<DisconnectKind> {
+        self.last_disconnect_kind
+    }
+
+    /// Returns the cumulative counters of this stream.
+    ///
+    /// This takes a snapshot, so reading the counters requires access to the stream, such as through the
+    /// [lock](crate::ReconnectReader::lock) of a split half when it is polled by another task.
+    /// The counters are plain fields rather than shared atomics: they are only updated while the stream is polled,
+    /// which already has exclusive access to it, and the durations are computed from the clock when the snapshot
+    /// is taken, which a counter could not do. A snapshot also keeps the counters consistent with each other.
+    /// To export them from elsewhere, the [events](Self::events) or the callbacks can signal when to take one.
+    pub fn stats(&self) -> ReconnectStats {
+        let mut stats = self.stats.clone();
+        let now = self.options.clock().now();
+        let disconnected_at = match &self.status {
+            Status::Connected => {
+                stats.uptime += stats.since_reset(self.connected_at, now);
+                None
+            }
+            Status::Disconnected(status) => Some(status.disconnected_at),
+            Status::Paused { disconnected_at } => Some(*disconnected_at),
+            Status::FailedAndExhausted | Status::Shutdown => None,
+        };
+        if let Some(disconnected_at) = disconnected_at {
+            stats.current_downtime = now.saturating_duration_since(disconnected_at);
+            stats.downtime += stats.since_reset(disconnected_at, now);
+        }
+        stats
+    }
+
+    /// Resets the cumulative counters of this stream, as if it had just been connected or disconnected.
+    /// The ongoing connection or outage is then only accounted from now on, whereas
+    /// the [current downtime](ReconnectStats::current_downtime) and the counters of the
+    /// [current connection](ReconnectStats::connection) are kept.
+    ///
+    /// This does not affect the connection, nor the reconnect attempts.
+    pub fn reset_stats(&mut self) {
+        self.stats = ReconnectStats {
+            connection: self.stats.connection.clone(),
+            reset_at: Some(self.options.clock().now()),
+            ..ReconnectStats::default()
+        };
+    }
+
     /// Connects or creates a handle to the [UnderlyingStream] item,
     /// using the default reconnect options.
-    pub async fn connect(ctor_arg: C) -> Result<Self, E> {
+    ///
+    /// Much like `TcpStream::connect`, this resolves once the initial connection is established.
+    /// With the default options, it fails right away if the initial connect attempt fails.
+    pub async fn connect(ctor_arg: C) -> Result<Self, ReconnectError<E>> {
         let options = ReconnectOptions::new();
         Self::connect_with_options(ctor_arg, options).await
     }
 
-    pub async fn connect_with_options(ctor_arg: C, options: ReconnectOptions) -> Result<Self, E> {
-        let tries = (**options.retries_to_attempt_fn())()
-            .map(Some)
-            .chain(once(None));
-        let mut result = None;
-        for (counter, maybe_delay) in tries.enumerate() {
-            match T::establish(ctor_arg.clone()).await {
-                Ok(inner) => {
+    /// Connects or creates a handle to the [UnderlyingStream] item, using the given reconnect options.
+    ///
+    /// Resolves once the initial connection is established. If the initial connect attempt fails,
+    /// this either fails with [InitialConnectFailed](ReconnectError::InitialConnectFailed) or keeps retrying,
+    /// according to [ReconnectOptions::with_exit_if_first_connect_fails] and [ReconnectOptions::with_first_connect_grace].
+    ///
+    /// See the [crate level example](crate) for a type alias which keeps the generics out of sight.
+    pub async fn connect_with_options(
+        ctor_arg: C,
+        options: ReconnectOptions,
+    ) -> Result<Self, ReconnectError<E>> {
+        Self::connect_with_endpoints(vec![ctor_arg], options).await
+    }
+
+    /// Connects to one of several equivalent endpoints, each described by a ctor arg.
+    /// Every connect attempt picks an endpoint according to the
+    /// [endpoint policy](ReconnectOptions::with_endpoint_policy), starting from the first one.
+    /// A refreshed ctor arg, if [configured](ReconnectOptions::with_ctor_arg_refresh), takes precedence.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ctor_args` is empty.
+    pub async fn connect_with_endpoints(
+        ctor_args: Vec<C>,
+        options: ReconnectOptions,
+    ) -> Result<Self, ReconnectError<E>> {
+        assert!(!ctor_args.is_empty(), "at least one ctor arg is required");
+        let mut endpoints = Endpoints {
+            ctor_args,
+            current: 0,
+        };
+        let mut stats = ReconnectStats::default();
+        let mut rate_limiter = AttemptRateLimiter::default();
+        let stream =
+            Self::establish_with_retries(&mut endpoints, &options, &mut stats, &mut rate_limiter)
+                .await?;
+        let mut this = Self::new_connected(stream, endpoints, options, stats);
+        this.rate_limiter = rate_limiter;
+        Ok(this)
+    }
+
+    /// Establishes a connection with the same retries as [connect_with_options](Self::connect_with_options),
+    /// and hands the bare underlying stream over instead of supervising it.
+    /// The connect callbacks are invoked as usual, but the returned stream is never reconnected.
+    pub async fn establish_once(
+        ctor_arg: C,
+        options: ReconnectOptions,
+    ) -> Result<T::Stream, ReconnectError<E>> {
+        let mut endpoints = Endpoints {
+            ctor_args: vec![ctor_arg],
+            current: 0,
+        };
+        Self::establish_with_retries(
+            &mut endpoints,
+            &options,
+            &mut ReconnectStats::default(),
+            &mut AttemptRateLimiter::default(),
+        )
+        .await
+    }
+
+    /// Supervises a stream which is already connected, instead of establishing a new connection.
+    /// `ctor_arg` is only used to re-establish the connection once it is lost.
+    ///
+    /// As no connection is established, the `on_connect_callback` is not invoked.
+    pub fn from_connected(stream: T::Stream, ctor_arg: C, options: ReconnectOptions) -> Self {
+        let endpoints = Endpoints {
+            ctor_args: vec![ctor_arg],
+            current: 0,
+        };
+        Self::new_connected(stream, endpoints, options, ReconnectStats::default())
+    }
+
+    /// Same as [connect_with_options](Self::connect_with_options), but time is measured and slept with `clock`,
+    /// including the reconnect delays, the timeouts and the [stats](Self::stats), so that tests can
+    /// [advance](TestClock::advance) it instead of waiting.
+    ///
+    /// If the initial connect attempt fails, this only resolves once the clock is advanced by another task.
+    #[cfg(feature = "test-util")]
+    pub async fn with_clock(
+        ctor_arg: C,
+        options: ReconnectOptions,
+        clock: TestClock,
+    ) -> Result<Self, ReconnectError<E>> {
+        Self::connect_with_options(ctor_arg, options.with_clock(Arc::new(clock))).await
+    }
+
+    /// Runs the initial connect loop until an attempt succeeds, or the options tell to give up.
+    async fn establish_with_retries(
+        endpoints: &mut Endpoints<C>,
+        options: &ReconnectOptions,
+        stats: &mut ReconnectStats,
+        rate_limiter: &mut AttemptRateLimiter,
+    ) -> Result<T::Stream, ReconnectError<E>> {
+        let mut ctor_arg = endpoints.current();
+        // the retries are generated upon the first failure, which may be passed to the generator
+        let mut attempts_tracker: Option<AttemptsTracker> = None;
+        let clock = options.clock();
+        let started_at = clock.now();
+        let first_delay = rate_limiter.schedule(options, Duration::ZERO);
+        if first_delay > Duration::ZERO {
+            debug!("Delaying the initial connect attempt by {:?}.", first_delay);
+            Self::sleep_unless_shutdown(options, first_delay).await?;
+        }
+        loop {
+            let attempt = attempts_tracker
+                .as_ref()
+                .map_or(0, |tracker| tracker.attempt_num);
+            let connect_timeout = options.connect_timeout(attempt);
+            match Self::establish_within(clock, connect_timeout, ctor_arg.clone()).await {
+                Ok(stream) => {
                     debug!("Initial connection succeeded.");
-                    (options.on_connect_callback())();
-                    result = Some(Ok(inner));
-                    break;
+                    let ctx = ReconnectContext {
+                        attempt: attempts_tracker.map_or(0, |tracker| tracker.attempt_num),
+                        downtime: clock.elapsed(started_at),
+                        next_delay: None,
+                    };
+                    if let Some(cb) = options.on_connect_control() {
+                        if cb(&ctx) == ControlFlow::Terminate {
+                            info!("Connect callback requested termination. Giving up.");
+                            return Err(ReconnectError::Terminated.named(options.name()));
+                        }
+                    }
+                    (options.on_connect_callback())(&ctx);
+                    if let Some(cb) = options.on_connect_async() {
+                        cb(&ctx).await;
+                    }
+                    endpoints.ctor_args[endpoints.current] = ctor_arg;
+                    return Ok(stream);
                 }
                 Err(e) => {
-                    error!("Connection failed due to: {:?}.", e);
-                    (options.on_connect_fail_callback())();
+                    stats.failed_attempts += 1;
+                    Self::report_connect_error(options, &e);
+                    let attempts_tracker = attempts_tracker.get_or_insert_with(|| {
+                        AttemptsTracker::new(options.initial_retries_for(e.error()))
+                    });
+                    let attempt = attempts_tracker.attempt_num;
+                    let fatal = matches!(e.error(), Some(err) if T::is_fatal_establish_error(err));
+                    let bail = fatal
+                        || options.exit_if_first_connect_fails()
+                            && stats.failed_attempts >= options.first_connect_grace() as u64;
+                    let next_delay = if bail {
+                        None
+                    } else {
+                        attempts_tracker
+                            .next_delay(options, clock.elapsed(started_at))
+                            .map(|delay| rate_limiter.schedule(options, delay))
+                    };
+                    (options.on_connect_fail_callback())(&ReconnectContext {
+                        attempt,
+                        downtime: clock.elapsed(started_at),
+                        next_delay,
+                    });
 
-                    if options.exit_if_first_connect_fails() {
-                        error!("Bailing after initial connection failure.");
-                        return Err(e);
-                    }
+                    let delay = match next_delay {
+                        Some(delay) => delay,
+                        None => {
+                            if fatal {
+                                error!("Initial connection failed with a fatal error. Giving up.");
+                            } else if bail {
+                                error!("Bailing after initial connection failure.");
+                            } else {
+                                error!("No more re-connect retries remaining. Never able to establish initial connection.");
+                            }
+                            let err = Self::initial_connect_error(e);
+                            Self::report_give_up(
+                                options,
+                                stats.failed_attempts as usize,
+                                clock.elapsed(started_at),
+                                Some(&err),
+                            );
+                            return Err(err.named(options.name()));
+                        }
+                    };
+
+                    debug!(
+                        "Will re-perform initial connect attempt #{} in {:?}.",
+                        attempts_tracker.attempt_num, delay
+                    );
 
-                    result = Some(Err(e));
+                    Self::sleep_unless_shutdown(options, delay).await?;
 
-                    if let Some(delay) = maybe_delay {
-                        debug!(
-                            "Will re-perform initial connect attempt #{} in {:?}.",
-                            counter + 1,
-                            delay
-                        );
+                    debug!(
+                        "Attempting reconnect #{} now.",
+                        attempts_tracker.attempt_num
+                    );
+                    if attempts_tracker.attempt_num == 1 {
+                        (options.on_reconnect_start_callback())(&ReconnectContext {
+                            attempt: 1,
+                            downtime: clock.elapsed(started_at),
+                            next_delay: None,
+                        });
+                    }
+                    ctor_arg = endpoints.select(options.endpoint_policy(), false);
+                    if let Some(refresh) = options.ctor_arg_refresh() {
+                        ctor_arg = refreshed_ctor_arg(refresh, ctor_arg).await;
+                    }
+                }
+            }
+        }
+    }
 
-                        #[cfg(feature = "tokio")]
-                        let sleep_fut = tokio::time::sleep(delay);
-                        #[cfg(feature = "async-std")]
-                        let sleep_fut = async_std::task::sleep(delay);
+    /// Establishes a connection, unless it does not complete within `timeout`.
+    /// The attempt is cancelled if it times out or if the returned future is dropped before completing.
+    /// With the `catch-panic` feature, a panic of the attempt is caught and reported as a failure.
+    async fn establish_within(
+        clock: &SharedClock,
+        timeout: Option<Duration>,
+        ctor_arg: C,
+    ) -> Result<T::Stream, ConnectFailure<E>> {
+        let token = CancelToken::new();
+        let guard = CancelOnDrop::new(token.clone());
+        let establish = T::establish_with_cancel(ctor_arg, token);
+        #[cfg(feature = "catch-panic")]
+        let establish = async move {
+            match std::panic::AssertUnwindSafe(establish).catch_unwind().await {
+                Ok(result) => result.map_err(ConnectFailure::Error),
+                Err(panic) => Err(ConnectFailure::Panicked(panic_message(panic))),
+            }
+        };
+        #[cfg(not(feature = "catch-panic"))]
+        let establish = establish.map(|result| result.map_err(ConnectFailure::Error));
+        let result = match timeout {
+            Some(timeout) => runtime::timeout(clock.sleep(timeout), establish).await,
+            None => Some(establish.await),
+        };
+        if result.is_some() {
+            guard.disarm();
+        }
+        result.unwrap_or(Err(ConnectFailure::TimedOut))
+    }
 
-                        sleep_fut.await;
+    /// Passes what was tried before giving up to the give up callback.
+    fn report_give_up(
+        options: &ReconnectOptions,
+        attempts: usize,
+        total_elapsed: Duration,
+        last_error: Option<&(dyn Error + 'static)>,
+    ) {
+        (options.on_give_up_callback())(&ReconnectSummary {
+            attempts,
+            total_elapsed,
+            last_error,
+        });
+    }
 
-                        debug!("Attempting reconnect #{} now.", counter + 1);
-                    }
+    /// Waits for `delay` before an initial connect attempt,
+    /// failing if the [shutdown signal](ReconnectOptions::with_shutdown_signal) fires meanwhile.
+    async fn sleep_unless_shutdown(
+        options: &ReconnectOptions,
+        delay: Duration,
+    ) -> Result<(), ReconnectError<E>> {
+        let clock = options.clock();
+        match options.shutdown_signal() {
+            Some(signal) => {
+                if let Either::Right(_) = future::select(clock.sleep(delay), signal.clone()).await {
+                    info!("Shutdown signal received, giving up the initial connect.");
+                    return Err(ReconnectError::Shutdown.named(options.name()));
                 }
             }
+            None => clock.sleep(delay).await,
         }
+        Ok(())
+    }
 
-        match result.unwrap() {
-            Ok(stream) => Ok(ReconnectStream {
-                status: Status::Connected,
-                stream,
-                options,
-                ctor_arg,
-            }),
-            Err(e) => {
-                error!("No more re-connect retries remaining. Never able to establish initial connection.");
-                Err(e)
+    /// Logs a failed connect attempt and passes its error to the callback.
+    fn report_connect_error(options: &ReconnectOptions, failure: &ConnectFailure<E>) {
+        match failure {
+            ConnectFailure::Error(err) => {
+                error!("Connection failed due to: {:?}.", err);
+                (options.on_connect_fail_err_callback())(err);
+            }
+            ConnectFailure::TimedOut => {
+                error!("Connection attempt timed out.");
+                (options.on_connect_fail_err_callback())(&ReconnectError::<E>::ConnectTimeout);
+            }
+            ConnectFailure::Panicked(message) => {
+                error!("Connection attempt panicked: {}.", message);
+                (options.on_connect_fail_err_callback())(&ReconnectError::<E>::EstablishPanic(
+                    message.clone(),
+                ));
+            }
+        }
+    }
+
+    fn initial_connect_error(failure: ConnectFailure<E>) -> ReconnectError<E> {
+        match failure {
+            ConnectFailure::Error(err) => ReconnectError::InitialConnectFailed(err),
+            ConnectFailure::TimedOut => ReconnectError::ConnectTimeout,
+            ConnectFailure::Panicked(message) => ReconnectError::EstablishPanic(message),
+        }
+    }
+
+    /// Drops the current underlying stream and starts reconnecting right away,
+    /// as if a disconnect had been detected.
+    /// The `on_disconnect_callback` is invoked and the configured retries are honored.
+    ///
+    /// Does nothing if the stream is already reconnecting or has given up.
+    pub fn force_reconnect(&mut self) {
+        if let Status::Connected = self.status {
+            info!("Reconnect forced");
+            self.last_disconnect_kind = Some(DisconnectKind::Forced);
+            self.start_reconnect(None);
+            self.wake_all();
+        }
+    }
+
+    /// Creates a new, independent stream with the same options and ctor args, which is not connected yet.
+    /// The connection is established when the stream is first polled.
+    ///
+    /// As there is no initial connect, failures of the first attempt are retried according to the
+    /// retries generator regardless of [ReconnectOptions::with_exit_if_first_connect_fails],
+    /// just like when reconnecting.
+    pub fn reconfigure_clone(&self) -> Self {
+        let endpoints = Endpoints {
+            ctor_args: self.endpoints.ctor_args.clone(),
+            current: 0,
+        };
+        let options = self.options.clone();
+        let mut reconnect_status = ReconnectStatus::new(
+            &options,
+            AttemptsTracker::new(options.retries_for::<E>(None)),
+        );
+        let connect_timeout = options.connect_timeout(0);
+        let clock = options.clock().clone();
+        let ctor_arg = endpoints.current();
+        let mut rate_limiter = AttemptRateLimiter::default();
+        let wait = rate_limiter.schedule(&options, Duration::ZERO);
+        reconnect_status.reconnect_attempt = async move {
+            if wait > Duration::ZERO {
+                clock.sleep(wait).await;
+            }
+            Self::establish_within(&clock, connect_timeout, ctor_arg).await
+        }
+        .boxed();
+        let mut this = Self::with_status(
+            Status::Disconnected(reconnect_status),
+            None,
+            endpoints,
+            options,
+            ReconnectStats::default(),
+        );
+        this.rate_limiter = rate_limiter;
+        this
+    }
+
+    fn new_connected(
+        stream: T::Stream,
+        endpoints: Endpoints<C>,
+        options: ReconnectOptions,
+        stats: ReconnectStats,
+    ) -> Self {
+        let mut this =
+            Self::with_status(Status::Connected, Some(stream), endpoints, options, stats);
+        this.last_connected_at = Some(this.connected_at);
+        this
+    }
+
+    fn with_status(
+        status: Status<T, C, I, E>,
+        stream: Option<T::Stream>,
+        endpoints: Endpoints<C>,
+        options: ReconnectOptions,
+        stats: ReconnectStats,
+    ) -> Self {
+        let shutdown_signal = options.shutdown_signal().cloned();
+        let now = options.clock().now();
+        let this = ReconnectStream {
+            status,
+            stream,
+            options,
+            endpoints,
+            read_waker: None,
+            write_waker: None,
+            inbound: VecDeque::new(),
+            outbound: VecDeque::new(),
+            drained_unflushed: 0,
+            unflushed: VecDeque::new(),
+            stats,
+            connected_at: now,
+            last_connected_at: None,
+            last_disconnected_at: None,
+            last_disconnect_kind: None,
+            last_read_at: now,
+            read_errors: 0,
+            idle_timer: None,
+            flush_timer: None,
+            on_connect_task: None,
+            shutdown_signal,
+            paused: false,
+            previous_attempts: None,
+            rate_limiter: AttemptRateLimiter::default(),
+            events: Vec::new(),
+            attempts: Vec::new(),
+            #[cfg(feature = "tokio")]
+            options_watch: None,
+            #[cfg(feature = "tokio")]
+            status_watch: watch::channel(ConnectionStatus::Disconnected).0,
+        };
+        this.publish_status();
+        this
+    }
+
+    /// Replaces the options of this stream without dropping the live connection.
+    /// The new options take effect on the next reconnection episode.
+    /// If the stream is reconnecting, the scheduled attempt is kept,
+    /// and the retries iterator is regenerated from the new options after it.
+    pub fn set_options(&mut self, options: ReconnectOptions) {
+        self.shutdown_signal = options.shutdown_signal().cloned();
+        self.options = options;
+        self.previous_attempts = None;
+        if let Status::Disconnected(status) = &mut self.status {
+            status.retries_outdated = true;
+        }
+    }
+
+    /// Keeps the options of this stream up to date with `options`, which is typically fed
+    /// by a centralized config: its current value is applied right away, and each new value
+    /// replaces the options with [set_options](Self::set_options) the next time this stream is polled.
+    /// Once the sender is dropped, the last options are kept.
+    ///
+    /// # Changing options mid-flight
+    ///
+    /// The retries and the limits on them, such as the max attempts and the reconnect duration,
+    /// take effect on the next reconnection episode, or after the scheduled attempt if reconnecting.
+    /// The callbacks, the name, the buffer capacities and the connect, idle and flush timeouts apply from the
+    /// next time they are used, without affecting an attempt or a timer already running, and the items buffered
+    /// beyond a lowered capacity are kept. The shutdown signal is replaced right away.
+    ///
+    /// The clock should not be changed, as the instants already recorded were measured with the previous one,
+    /// nor the item types of the item hooks and of [with_resend_unflushed](ReconnectOptions::with_resend_unflushed).
+    #[cfg(feature = "tokio")]
+    pub fn watch_options(&mut self, mut options: watch::Receiver<ReconnectOptions>) {
+        let current = options.borrow_and_update().clone();
+        self.set_options(current);
+        self.options_watch = Some(options);
+    }
+
+    /// Applies the latest value of the options watch, if it changed.
+    #[cfg(feature = "tokio")]
+    fn poll_options_watch(&mut self) {
+        let (options, closed) = match &mut self.options_watch {
+            Some(watch) => {
+                let closed = watch.has_changed().is_err();
+                let latest = watch.borrow_and_update();
+                // the last value sent before the sender was dropped may not have been seen yet
+                (latest.has_changed().then(|| latest.clone()), closed)
+            }
+            None => return,
+        };
+        if closed {
+            debug!("Options watch closed, keeping the last options.");
+            self.options_watch = None;
+        }
+        if let Some(options) = options {
+            info!("Options changed, applying them.");
+            self.set_options(options);
+        }
+    }
+
+    /// Stops reconnecting until [resume](Self::resume) is called.
+    /// A reconnect attempt in progress is abandoned, and the retries iterator is not consumed while paused.
+    /// The live connection, if any, is kept, but a later disconnect will not be followed by reconnects.
+    pub fn pause(&mut self) {
+        self.paused = true;
+        if let Status::Disconnected(_) = self.status {
+            info!("Reconnection is paused");
+            self.enter_paused();
+        }
+    }
+
+    /// Resumes reconnecting after [pause](Self::pause),
+    /// starting over with a fresh iterator from the retries generator.
+    pub fn resume(&mut self) {
+        self.paused = false;
+        if let Status::Paused { disconnected_at } = self.status {
+            info!("Reconnection is resumed");
+            let attempts_tracker = self.resumed_attempts();
+            let mut reconnect_status = ReconnectStatus::new(&self.options, attempts_tracker);
+            reconnect_status.disconnected_at = disconnected_at;
+            self.status = Status::Disconnected(reconnect_status);
+            self.schedule_attempt(None, true);
+            self.wake_all();
+        }
+    }
+
+    /// Leaves the reconnection episode for the paused state,
+    /// keeping the attempts for later if the schedule is persistent.
+    fn enter_paused(&mut self) {
+        let disconnected_at = match self.status {
+            Status::Disconnected(ref status) => status.disconnected_at,
+            _ => return,
+        };
+        if let Status::Disconnected(status) =
+            std::mem::replace(&mut self.status, Status::Paused { disconnected_at })
+        {
+            if self.options.persistent_schedule() {
+                self.previous_attempts = Some((status.attempts_tracker, 0, disconnected_at));
             }
         }
+        self.publish_status();
+    }
+
+    /// Returns the attempts to go on with after a pause,
+    /// which start over unless the schedule is persistent.
+    fn resumed_attempts(&mut self) -> AttemptsTracker {
+        match self.previous_attempts.take() {
+            Some((attempts_tracker, _, _)) if self.options.persistent_schedule() => {
+                attempts_tracker
+            }
+            _ => AttemptsTracker::new(self.options.retries_for::<E>(None)),
+        }
+    }
+
+    fn register_read_waker(&mut self, cx: &Context) {
+        register(&mut self.read_waker, cx);
+    }
+
+    fn register_write_waker(&mut self, cx: &Context) {
+        register(&mut self.write_waker, cx);
+    }
+
+    fn wake_all(&self) {
+        self.publish_status();
+        if let Some(waker) = &self.read_waker {
+            waker.wake_by_ref();
+        }
+        if let Some(waker) = &self.write_waker {
+            waker.wake_by_ref();
+        }
+    }
+
+    /// Notifies the [changed](Self::changed) futures if the status is not the last one notified.
+    fn publish_status(&self) {
+        #[cfg(feature = "tokio")]
+        {
+            let status = self.status();
+            self.status_watch.send_if_modified(|current| {
+                let modified = *current != status;
+                *current = status;
+                modified
+            });
+        }
+    }
+
+    /// `reason` is the error which caused the disconnect, if known.
+    /// Whichever side detected it, the other side is woken up to observe the same disconnect,
+    /// since its pending poll was registered with the underlying stream which is now dropped.
+    fn on_disconnect(&mut self, cx: &mut Context, reason: Option<&E>) {
+        self.start_reconnect(reason);
+        cx.waker().wake_by_ref();
+        self.wake_all();
+    }
+
+    /// Starts reconnecting after the live connection was lost, recording what caused it.
+    fn on_connection_lost(&mut self, cx: &mut Context, kind: DisconnectKind, reason: Option<&E>) {
+        self.last_disconnect_kind = Some(kind);
+        self.on_disconnect(cx, reason);
     }
 
-    fn on_disconnect(mut self: Pin<&mut Self>, cx: &mut Context) {
-        match &mut self.status {
-            // initial disconnect
+    fn start_reconnect(&mut self, reason: Option<&E>) {
+        match self.status {
             Status::Connected => {
                 error!("Disconnect occurred");
-                (self.options.on_disconnect_callback())();
-                self.status = Status::Disconnected(ReconnectStatus::new(&self.options));
+                // the lost connection is dropped right away, however the disconnect was detected
+                self.stream = None;
+                self.stats.disconnects += 1;
+                self.emit(ConnEvent::Disconnected);
+                self.drained_unflushed = 0;
+                self.read_errors = 0;
+                self.flush_timer = None;
+                let now = self.options.clock().now();
+                self.last_disconnected_at = Some(now);
+                self.stats.uptime += self.stats.since_reset(self.connected_at, now);
+                // resend items which were not confirmed flushed before the buffered ones
+                while let Some(item) = self.unflushed.pop_back() {
+                    self.outbound.push_front(item);
+                }
+                let terminate = self.options.on_disconnect_control().map(|cb| {
+                    cb(&ReconnectContext {
+                        attempt: 0,
+                        downtime: Duration::ZERO,
+                        next_delay: None,
+                    })
+                }) == Some(ControlFlow::Terminate);
+                if terminate || self.options.never_reconnects() {
+                    if terminate {
+                        info!("Disconnect callback requested termination. Giving up.");
+                    } else {
+                        info!("Reconnecting is disabled. Giving up.");
+                    }
+                    (self.options.on_disconnect_callback())(&ReconnectContext {
+                        attempt: 0,
+                        downtime: Duration::ZERO,
+                        next_delay: None,
+                    });
+                    Self::report_give_up(
+                        &self.options,
+                        0,
+                        Duration::ZERO,
+                        reason.map(|err| err as &(dyn Error + 'static)),
+                    );
+                    self.status = Status::FailedAndExhausted;
+                    self.emit(ConnEvent::Dead);
+                    return;
+                }
+                let connected_for = self.options.clock().elapsed(self.connected_at);
+                let unhealthy = matches!(
+                    self.options.min_healthy_duration(),
+                    Some(min) if connected_for < min
+                );
+                let persistent = self.options.persistent_schedule();
+                let previous_attempts =
+                    self.previous_attempts
+                        .take()
+                        .filter(|(_, _, connected_at)| {
+                            let unstable = matches!(
+                                self.options.reset_after_stable(),
+                                Some(period) if self.options.clock().elapsed(*connected_at) < period
+                            );
+                            if (unstable || unhealthy) && !persistent {
+                                debug!(
+                                    "Connection was not stable, continuing the previous backoff."
+                                );
+                            }
+                            unstable || unhealthy || persistent
+                        });
+                let mut reconnect_status = match previous_attempts {
+                    Some((attempts_tracker, failures, _)) => {
+                        let mut reconnect_status =
+                            ReconnectStatus::new(&self.options, attempts_tracker);
+                        if unhealthy {
+                            reconnect_status.failures = failures;
+                        }
+                        reconnect_status
+                    }
+                    None => {
+                        let retries = self.options.retries_for(reason);
+                        ReconnectStatus::new(&self.options, AttemptsTracker::new(retries))
+                    }
+                };
+                if unhealthy {
+                    warn!(
+                        "Connection dropped after {:?}, counting it as a failed attempt.",
+                        connected_for
+                    );
+                    self.stats.failed_attempts += 1;
+                    reconnect_status.failures += 1;
+                }
+                #[cfg(feature = "tracing")]
+                tracing::warn!(parent: &reconnect_status.span, "Disconnect occurred");
+
+                if self.paused {
+                    info!("Reconnection is paused");
+                    (self.options.on_disconnect_callback())(&ReconnectContext {
+                        attempt: 0,
+                        downtime: Duration::ZERO,
+                        next_delay: None,
+                    });
+                    let disconnected_at = reconnect_status.disconnected_at;
+                    if persistent {
+                        self.previous_attempts =
+                            Some((reconnect_status.attempts_tracker, 0, disconnected_at));
+                    }
+                    self.status = Status::Paused { disconnected_at };
+                    return;
+                }
+
+                self.status = Status::Disconnected(reconnect_status);
+                self.schedule_attempt(Some(ReconnectOptions::on_disconnect_callback), true);
             }
-            Status::Disconnected(_) => {
-                (self.options.on_connect_fail_callback())();
+            Status::Disconnected(ref mut reconnect_status) => {
+                reconnect_status.failures += 1;
+                self.stats.failed_attempts += 1;
+                self.emit(ConnEvent::ReconnectFailed);
+                self.schedule_attempt(Some(ReconnectOptions::on_connect_fail_callback), false);
             }
-            Status::FailedAndExhausted => {
-                unreachable!("on_disconnect will not occur for already exhausted state.")
+            Status::Paused { .. } | Status::FailedAndExhausted | Status::Shutdown => {
+                unreachable!("on_disconnect will not occur while not trying to connect.")
             }
+        }
+    }
+
+    /// Pulls the next delay and schedules the next attempt, or gives up.
+    /// `callback` is invoked with the resulting context.
+    /// `episode_start` is true for the first attempt after the connection was lost or reconnecting was resumed.
+    fn schedule_attempt(
+        &mut self,
+        callback: Option<fn(&ReconnectOptions) -> &Callback>,
+        episode_start: bool,
+    ) {
+        let reconnect_status = match &mut self.status {
+            Status::Disconnected(reconnect_status) => reconnect_status,
+            _ => unreachable!(),
         };
 
-        let ctor_arg = self.ctor_arg.clone();
+        if reconnect_status.retries_outdated {
+            debug!("Options were replaced, regenerating the retries.");
+            reconnect_status.attempts_tracker.retries_remaining =
+                self.options.retries_for::<E>(None);
+            reconnect_status.attempts_tracker.peeked = None;
+            reconnect_status.retries_outdated = false;
+        }
 
-        // this is ensured to be true now
-        if let Status::Disconnected(reconnect_status) = &mut self.status {
-            let next_duration = match reconnect_status.attempts_tracker.retries_remaining.next() {
-                Some(duration) => duration,
-                None => {
-                    error!("No more re-connect retries remaining. Giving up.");
-                    self.status = Status::FailedAndExhausted;
-                    cx.waker().wake_by_ref();
-                    return;
+        let attempt = reconnect_status.attempts_tracker.attempt_num;
+        let elapsed = self
+            .options
+            .clock()
+            .elapsed(reconnect_status.disconnected_at);
+        let (rate_limiter, options) = (&mut self.rate_limiter, &self.options);
+        let next_duration = match self.options.circuit_breaker() {
+            Some((threshold, cooldown)) if reconnect_status.failures >= threshold => {
+                if !reconnect_status.circuit_open {
+                    warn!(
+                        "{} reconnect attempts failed in a row, opening the circuit.",
+                        threshold
+                    );
+                    reconnect_status.circuit_open = true;
                 }
-            };
+                reconnect_status
+                    .attempts_tracker
+                    .next_probe(&self.options, elapsed, cooldown)
+            }
+            _ => reconnect_status
+                .attempts_tracker
+                .next_delay(&self.options, elapsed),
+        }
+        .map(|delay| rate_limiter.schedule(options, delay));
+        let ctx = ReconnectContext {
+            attempt,
+            downtime: elapsed,
+            next_delay: next_duration,
+        };
+        if let Some(callback) = callback {
+            (callback(&self.options))(&ctx);
+        }
 
-            #[cfg(feature = "tokio")]
-            let future_instant = tokio::time::sleep(next_duration);
-            #[cfg(feature = "async-std")]
-            let future_instant = async_std::task::sleep(next_duration);
+        let next_duration = match next_duration {
+            Some(duration) => duration,
+            None => {
+                error!("No more re-connect retries remaining. Giving up.");
+                #[cfg(feature = "tracing")]
+                tracing::error!(
+                    parent: &reconnect_status.span,
+                    "No more re-connect retries remaining. Giving up."
+                );
+                let now = self.options.clock().now();
+                self.stats.downtime += self
+                    .stats
+                    .since_reset(reconnect_status.disconnected_at, now);
+                Self::report_give_up(
+                    &self.options,
+                    attempt,
+                    elapsed,
+                    reconnect_status
+                        .last_error
+                        .as_deref()
+                        .map(|err| err as &(dyn Error + 'static)),
+                );
+                self.status = Status::FailedAndExhausted;
+                self.emit(ConnEvent::Dead);
+                return;
+            }
+        };
+
+        let clock = self.options.clock().clone();
+        let future_instant = clock.sleep(next_duration);
 
-            reconnect_status.attempts_tracker.attempt_num += 1;
-            let cur_num = reconnect_status.attempts_tracker.attempt_num;
-            reconnect_status.reconnect_attempt = async move {
-                future_instant.await;
-                debug!("Attempting reconnect #{} now.", cur_num);
-                T::establish(ctor_arg).await
+        reconnect_status.scheduled_delay = Some((next_duration, clock.now()));
+        let cur_num = reconnect_status.attempts_tracker.attempt_num;
+        let ctor_arg = self
+            .endpoints
+            .select(self.options.endpoint_policy(), episode_start);
+        let refresh = self.options.ctor_arg_refresh().cloned();
+        let connect_timeout = self.options.connect_timeout(cur_num);
+        let reconnect_start_callback = if episode_start {
+            Some(self.options.on_reconnect_start_callback().clone())
+        } else {
+            None
+        };
+        let disconnected_at = reconnect_status.disconnected_at;
+        let attempt = async move {
+            future_instant.await;
+            debug!("Attempting reconnect #{} now.", cur_num);
+            if let Some(callback) = reconnect_start_callback {
+                callback(&ReconnectContext {
+                    attempt: cur_num,
+                    downtime: clock.elapsed(disconnected_at),
+                    next_delay: None,
+                });
             }
-            .boxed();
+            let ctor_arg = match refresh {
+                Some(refresh) => refreshed_ctor_arg(&refresh, ctor_arg).await,
+                None => ctor_arg,
+            };
+            Self::establish_within(&clock, connect_timeout, ctor_arg).await
+        };
+        #[cfg(feature = "tracing")]
+        let attempt = {
+            let span = &reconnect_status.span;
+            span.record("attempt", cur_num);
+            span.record("delay", tracing::field::debug(next_duration));
+            attempt.instrument(span.clone())
+        };
+        reconnect_status.reconnect_attempt = attempt.boxed();
 
-            debug!(
-                "Will perform reconnect attempt #{} in {:?}.",
-                reconnect_status.attempts_tracker.attempt_num, next_duration
+        debug!(
+            "Will perform reconnect attempt #{} in {:?}.",
+            reconnect_status.attempts_tracker.attempt_num, next_duration
+        );
+    }
+
+    /// Stops reconnecting for good, dropping the connection if any.
+    fn shut_down(&mut self) {
+        self.stop(Status::Shutdown);
+    }
+
+    /// Gives up reconnecting as if the retries were exhausted, dropping the connection if any.
+    pub(crate) fn give_up(&mut self) {
+        if self.reconnection_enabled() {
+            error!("Giving up along with the paired stream.");
+            let (attempts, total_elapsed, last_error) = match &self.status {
+                Status::Disconnected(status) => (
+                    status.attempts_tracker.attempt_num,
+                    self.options.clock().elapsed(status.disconnected_at),
+                    status.last_error.as_deref(),
+                ),
+                Status::Paused { disconnected_at } => {
+                    (0, self.options.clock().elapsed(*disconnected_at), None)
+                }
+                _ => (0, Duration::ZERO, None),
+            };
+            Self::report_give_up(
+                &self.options,
+                attempts,
+                total_elapsed,
+                last_error.map(|err| err as &(dyn Error + 'static)),
             );
+            self.stop(Status::FailedAndExhausted);
+            self.emit(ConnEvent::Dead);
+        }
+    }
 
-            cx.waker().wake_by_ref();
+    /// Drops the connection if any, and waits without attempting to reconnect nor consuming the retries
+    /// until [reconnect_now](Self::reconnect_now) is called.
+    pub(crate) fn hold(&mut self) {
+        if let Status::Connected = self.status {
+            let paused = std::mem::replace(&mut self.paused, true);
+            self.last_disconnect_kind = Some(DisconnectKind::Forced);
+            self.start_reconnect(None);
+            self.paused = paused;
+            self.wake_all();
+        } else {
+            self.enter_paused();
         }
     }
 
-    fn poll_disconnect(mut self: Pin<&mut Self>, cx: &mut Context) {
-        let (attempt, attempt_num) = match &mut self.status {
-            Status::Connected => unreachable!(),
-            Status::Disconnected(ref mut status) => (
-                Pin::new(&mut status.reconnect_attempt),
-                status.attempts_tracker.attempt_num,
-            ),
-            Status::FailedAndExhausted => unreachable!(),
+    /// Attempts to reconnect right away after [hold](Self::hold),
+    /// then follows the retries as usual if the attempt fails.
+    /// Does nothing if reconnection was [paused](Self::pause).
+    pub(crate) fn reconnect_now(&mut self) {
+        let disconnected_at = match self.status {
+            Status::Paused { disconnected_at } if !self.paused => disconnected_at,
+            _ => return,
+        };
+        let attempts_tracker = self.resumed_attempts();
+        let mut reconnect_status = ReconnectStatus::new(&self.options, attempts_tracker);
+        reconnect_status.disconnected_at = disconnected_at;
+        let ctor_arg = self.endpoints.select(self.options.endpoint_policy(), true);
+        let refresh = self.options.ctor_arg_refresh().cloned();
+        let connect_timeout = self
+            .options
+            .connect_timeout(reconnect_status.attempts_tracker.attempt_num);
+        let clock = self.options.clock().clone();
+        let wait = self.rate_limiter.schedule(&self.options, Duration::ZERO);
+        reconnect_status.reconnect_attempt = async move {
+            if wait > Duration::ZERO {
+                clock.sleep(wait).await;
+            }
+            let ctor_arg = match refresh {
+                Some(refresh) => refreshed_ctor_arg(&refresh, ctor_arg).await,
+                None => ctor_arg,
+            };
+            Self::establish_within(&clock, connect_timeout, ctor_arg).await
+        }
+        .boxed();
+        self.status = Status::Disconnected(reconnect_status);
+        self.wake_all();
+    }
+
+    /// Leaves the current state for `status`, accounting the time spent in it, and drops the connection if any.
+    fn stop(&mut self, status: Status<T, C, I, E>) {
+        let stats = &mut self.stats;
+        let now = self.options.clock().now();
+        match &self.status {
+            Status::Connected => stats.uptime += stats.since_reset(self.connected_at, now),
+            Status::Disconnected(status) => {
+                stats.downtime += stats.since_reset(status.disconnected_at, now)
+            }
+            Status::Paused { disconnected_at } => {
+                stats.downtime += stats.since_reset(*disconnected_at, now)
+            }
+            Status::FailedAndExhausted | Status::Shutdown => {}
+        }
+        self.stream = None;
+        self.status = status;
+        self.wake_all();
+    }
+
+    /// Drives the reconnection while disconnected, unless the shutdown signal completed.
+    fn poll_reconnect(&mut self, cx: &mut Context) {
+        #[cfg(feature = "tokio")]
+        self.poll_options_watch();
+        if !matches!(self.status, Status::Disconnected(_) | Status::Paused { .. }) {
+            return;
+        }
+        if let Some(signal) = &mut self.shutdown_signal {
+            if signal.poll_unpin(cx).is_ready() {
+                info!("Shutdown signal received, no longer reconnecting.");
+                self.shutdown_signal = None;
+                self.shut_down();
+                return;
+            }
+        }
+        if let Status::Disconnected(_) = self.status {
+            self.poll_disconnect(cx);
+        }
+    }
+
+    fn poll_disconnect(&mut self, cx: &mut Context) {
+        let status = match &mut self.status {
+            Status::Disconnected(status) => status,
+            _ => unreachable!(),
         };
+        let attempt_num = status.attempts_tracker.attempt_num;
+        let disconnected_at = status.disconnected_at;
 
-        match attempt.poll(cx) {
+        match Pin::new(&mut status.reconnect_attempt).poll(cx) {
             Poll::Ready(Ok(underlying_io)) => {
                 info!("Connection re-established");
+                #[cfg(feature = "tracing")]
+                tracing::info!(parent: &status.span, "Connection re-established");
+                self.emit_attempt(Ok(()));
                 cx.waker().wake_by_ref();
-                self.status = Status::Connected;
-                (self.options.on_connect_callback())();
-                self.stream = underlying_io;
+                self.wake_all();
+                let now = self.options.clock().now();
+                self.stats.downtime += self.stats.since_reset(disconnected_at, now);
+                let ctx = ReconnectContext {
+                    attempt: attempt_num,
+                    downtime: now.saturating_duration_since(disconnected_at),
+                    next_delay: None,
+                };
+                if let Some(cb) = self.options.on_connect_control() {
+                    if cb(&ctx) == ControlFlow::Terminate {
+                        info!("Connect callback requested termination. Giving up.");
+                        Self::report_give_up(&self.options, attempt_num, ctx.downtime, None);
+                        self.status = Status::FailedAndExhausted;
+                        self.emit(ConnEvent::Dead);
+                        return;
+                    }
+                }
+                self.stats.reconnects += 1;
+                self.stats.connection = ConnectionStats::default();
+                self.connected_at = now;
+                self.last_connected_at = Some(now);
+                if let Status::Disconnected(status) =
+                    std::mem::replace(&mut self.status, Status::Connected)
+                {
+                    if self.options.reset_after_stable().is_some()
+                        || self.options.min_healthy_duration().is_some()
+                        || self.options.persistent_schedule()
+                    {
+                        let mut attempts_tracker = status.attempts_tracker;
+                        attempts_tracker.near_exhaustion_reported = false;
+                        self.previous_attempts = Some((attempts_tracker, status.failures, now));
+                    }
+                }
+                (self.options.on_connect_callback())(&ctx);
+                self.on_connect_task = self.options.on_connect_async().map(|cb| cb(&ctx));
+                self.emit(ConnEvent::Connected);
+                self.stream = Some(underlying_io);
+                self.last_read_at = now;
+                self.idle_timer = None;
             }
             Poll::Ready(Err(err)) => {
-                error!("Connection attempt #{} failed: {:?}", attempt_num, err);
-                self.on_disconnect(cx);
+                info!("Connection attempt #{} failed", attempt_num);
+                Self::report_connect_error(&self.options, &err);
+                if matches!(err.error(), Some(err) if T::is_fatal_establish_error(err)) {
+                    error!("Connection failed with a fatal error. Giving up.");
+                    let err = Arc::new(err.into_error());
+                    self.emit_attempt(Err(&err));
+                    let now = self.options.clock().now();
+                    self.stats.failed_attempts += 1;
+                    self.stats.downtime += self.stats.since_reset(disconnected_at, now);
+                    Self::report_give_up(
+                        &self.options,
+                        attempt_num,
+                        now.saturating_duration_since(disconnected_at),
+                        Some(&*err),
+                    );
+                    self.status = Status::FailedAndExhausted;
+                    self.emit(ConnEvent::Dead);
+                    self.wake_all();
+                } else {
+                    let err = Arc::new(err.into_error());
+                    self.emit_attempt(Err(&err));
+                    if let Status::Disconnected(status) = &mut self.status {
+                        status.last_error = Some(err);
+                    }
+                    self.on_disconnect(cx, None);
+                }
             }
             Poll::Pending => {}
         }
     }
 
-    fn is_write_disconnect_detected<X>(&self, poll_result: &Poll<Result<X, E>>) -> bool {
+    /// Polls the future of the async on_connect callback of the current connection, if any.
+    fn poll_on_connect_task(&mut self, cx: &mut Context) -> Poll<()> {
+        if let Some(task) = self.on_connect_task.as_mut() {
+            ready!(task.as_mut().poll(cx));
+            self.on_connect_task = None;
+        }
+        Poll::Ready(())
+    }
+
+    /// Returns true if nothing was read for longer than the idle timeout.
+    fn poll_idle_timeout(&mut self, cx: &mut Context) -> bool {
+        let timeout = match self.options.idle_timeout() {
+            Some(timeout) => timeout,
+            None => return false,
+        };
+        loop {
+            let clock = self.options.clock();
+            let remaining = timeout.saturating_sub(clock.elapsed(self.last_read_at));
+            if remaining.is_zero() {
+                self.idle_timer = None;
+                return true;
+            }
+            let timer = self
+                .idle_timer
+                .get_or_insert_with(|| clock.sleep(remaining));
+            if timer.poll_unpin(cx).is_pending() {
+                return false;
+            }
+            // items may have been read since the timer was armed, so check again
+            self.idle_timer = None;
+        }
+    }
+
+    /// Arms the flush timer while `poll` is pending, and returns true once it expired.
+    fn flush_timed_out<R>(&mut self, cx: &mut Context, poll: &Poll<R>) -> bool {
+        let timeout = match (poll, self.options.flush_timeout()) {
+            (Poll::Pending, Some(timeout)) => timeout,
+            _ => {
+                self.flush_timer = None;
+                return false;
+            }
+        };
+        let clock = self.options.clock();
+        let timer = self.flush_timer.get_or_insert_with(|| clock.sleep(timeout));
+        if timer.poll_unpin(cx).is_pending() {
+            return false;
+        }
+        error!("Flush did not complete within the flush timeout");
+        self.flush_timer = None;
+        true
+    }
+
+    /// Sends the items buffered while disconnected to the underlying stream, flushing them by
+    /// [batches](ReconnectOptions::with_flush_batch_size) so that the backlog is not piled up in the underlying sink.
+    fn poll_drain_outbound<X: 'static>(
+        &mut self,
+        cx: &mut Context,
+    ) -> Poll<Result<(), ReconnectError<E>>>
+    where
+        T::Stream: Sink<X, Error = E>,
+    {
+        loop {
+            if self.drained_unflushed > 0
+                && (self.outbound.is_empty()
+                    || self.drained_unflushed >= self.options.flush_batch_size())
+            {
+                let poll = Pin::new(self.stream.as_mut().unwrap()).poll_flush(cx);
+                if let Some(err) = Self::write_disconnect_error(&poll) {
+                    self.on_connection_lost(cx, DisconnectKind::Write, Some(err));
+                    return Poll::Pending;
+                }
+                if self.flush_timed_out(cx, &poll) {
+                    self.on_connection_lost(cx, DisconnectKind::FlushTimeout, None);
+                    return Poll::Pending;
+                }
+                ready!(poll)?;
+                self.unflushed.clear();
+                self.drained_unflushed = 0;
+            }
+            if self.outbound.is_empty() {
+                return Poll::Ready(Ok(()));
+            }
+
+            if !self.outbound.front().is_some_and(|item| item.is::<X>()) {
+                // buffered through another Sink impl of the underlying stream, which drains them
+                return Poll::Ready(Err(ReconnectError::ItemTypeMismatch));
+            }
+
+            let poll = Pin::new(self.stream.as_mut().unwrap()).poll_ready(cx);
+            if let Some(err) = Self::write_disconnect_error(&poll) {
+                self.on_connection_lost(cx, DisconnectKind::Write, Some(err));
+                return Poll::Pending;
+            }
+            ready!(poll)?;
+
+            let item = match self.outbound.pop_front().map(|item| item.downcast::<X>()) {
+                Some(Ok(item)) => item,
+                _ => unreachable!("the type of the next outbound item was checked"),
+            };
+            self.start_send_underlying(*item)?;
+            self.drained_unflushed += 1;
+        }
+    }
+
+    /// Sends an item to the underlying stream,
+    /// keeping a copy until flushed if unflushed items are resent on reconnect.
+    fn start_send_underlying<X: 'static>(&mut self, item: X) -> Result<(), ReconnectError<E>>
+    where
+        T::Stream: Sink<X, Error = E>,
+    {
+        let stream = match self.stream.as_mut() {
+            Some(stream) => stream,
+            None => return Err(ReconnectError::Disconnected),
+        };
+        let copy = self
+            .options
+            .unflushed_item_cloner()
+            .and_then(|clone| clone(&item));
+        if let Some(hook) = self.options.on_item_sent() {
+            hook(&item);
+        }
+        Pin::new(stream).start_send(item)?;
+        self.stats.connection.items_sent_since_connect += 1;
+        self.unflushed.extend(copy);
+        Ok(())
+    }
+
+    /// Returns the error if it is considered a disconnect.
+    fn write_disconnect_error<X>(poll_result: &Poll<Result<X, E>>) -> Option<&E> {
         match poll_result {
-            Poll::Ready(Err(err)) => T::is_write_disconnect_error(err),
-            _ => false,
+            Poll::Ready(Err(err)) if T::is_write_disconnect_error(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the refreshed ctor arg, or `ctor_arg` if the refresh returned another type.
+async fn refreshed_ctor_arg<C: 'static>(refresh: &CtorArgRefresh, ctor_arg: C) -> C {
+    match refresh().await.downcast::<C>() {
+        Ok(refreshed) => *refreshed,
+        Err(_) => {
+            error!(
+                "Ctor arg refresh returned another type than the ctor arg, using the previous one."
+            );
+            ctor_arg
+        }
+    }
+}
+
+impl<T, C, I, E> ReconnectStream<T, C, I, E>
+where
+    T: UnderlyingStream<C, I, E>,
+    T::Stream: Stream<Item = I>,
+    C: Clone + Send + Unpin + 'static,
+    I: Unpin + 'static,
+    E: Error + Unpin + 'static,
+{
+    /// Reads the next item to yield from the underlying stream.
+    /// Returns `Pending` if a disconnect was detected.
+    fn poll_read(&mut self, cx: &mut Context) -> Poll<Option<I>> {
+        let poll = match Pin::new(self.stream.as_mut().unwrap()).poll_next(cx) {
+            Poll::Ready(poll) => poll,
+            Poll::Pending => {
+                if self.poll_idle_timeout(cx) {
+                    error!("Nothing was read within the idle timeout");
+                    self.on_connection_lost(cx, DisconnectKind::IdleTimeout, None);
+                }
+                return Poll::Pending;
+            }
+        };
+        self.last_read_at = self.options.clock().now();
+        if let Some(poll) = poll {
+            if T::is_read_disconnect_error(&poll) {
+                self.read_errors += 1;
+                if self.read_errors < self.options.disconnect_error_threshold() {
+                    warn!(
+                        "Tolerating disconnect error {} of {}",
+                        self.read_errors,
+                        self.options.disconnect_error_threshold()
+                    );
+                    cx.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+                self.on_connection_lost(cx, DisconnectKind::Read, T::read_disconnect_error(&poll));
+                return Poll::Pending;
+            }
+            self.read_errors = 0;
+            match T::classify_item(poll) {
+                ItemAction::Yield(item) => {
+                    self.stats.connection.items_received_since_connect += 1;
+                    if let Some(hook) = self.options.on_item_received() {
+                        hook(&item);
+                    }
+                    Poll::Ready(Some(item))
+                }
+                ItemAction::Disconnect => {
+                    self.on_connection_lost(cx, DisconnectKind::Read, None);
+                    Poll::Pending
+                }
+                ItemAction::Drop => {
+                    self.stats.dropped_inbound += 1;
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+                ItemAction::Terminate => {
+                    info!("Connection terminated by the peer");
+                    self.shut_down();
+                    Poll::Ready(None)
+                }
+            }
+        } else if T::is_clean_eof(self.stream.as_ref().unwrap()) {
+            info!("Connection ended by the peer");
+            self.shut_down();
+            Poll::Ready(None)
+        } else {
+            self.on_connection_lost(cx, DisconnectKind::Read, None);
+            Poll::Pending
         }
     }
 }
@@ -301,107 +2122,390 @@ where
     T: UnderlyingStream<C, I, E>,
     T::Stream: Stream<Item = I>,
     C: Clone + Send + Unpin + 'static,
-    I: Unpin,
-    E: Error + Unpin,
+    I: Unpin + 'static,
+    E: Error + Unpin + 'static,
 {
     type Item = I;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.register_read_waker(cx);
+        self.poll_reconnect(cx);
+        if matches!(self.status, Status::Connected) && self.poll_on_connect_task(cx).is_pending() {
+            return Poll::Pending;
+        }
         match self.status {
-            Status::Connected => {
-                let poll = ready!(Pin::new(&mut self.stream).poll_next(cx));
-                if let Some(poll) = poll {
-                    if T::is_read_disconnect_error(&poll) {
-                        self.on_disconnect(cx);
-                        Poll::Pending
-                    } else {
-                        Poll::Ready(Some(poll))
+            Status::Connected => match self.options.inbound_buffer_capacity() {
+                Some(capacity) => {
+                    // once the buffer is full, the underlying stream is not polled anymore
+                    while self.inbound.len() < capacity && matches!(self.status, Status::Connected)
+                    {
+                        match self.poll_read(cx) {
+                            Poll::Ready(Some(item)) => self.inbound.push_back(item),
+                            _ => break,
+                        }
+                    }
+                    match self.inbound.pop_front() {
+                        Some(item) => Poll::Ready(Some(item)),
+                        None if matches!(self.status, Status::Shutdown) => Poll::Ready(None),
+                        None => Poll::Pending,
                     }
-                } else {
-                    self.on_disconnect(cx);
-                    Poll::Pending
                 }
-            }
-            Status::Disconnected(_) => {
-                self.poll_disconnect(cx);
-                Poll::Pending
-            }
-            Status::FailedAndExhausted => Poll::Ready(None),
+                None => self.poll_read(cx),
+            },
+            _ if !self.inbound.is_empty() => Poll::Ready(self.inbound.pop_front()),
+            Status::Disconnected(_) | Status::Paused { .. } => Poll::Pending,
+            Status::FailedAndExhausted | Status::Shutdown => Poll::Ready(None),
         }
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        self.stream.size_hint()
+        let buffered = self.inbound.len();
+        match &self.stream {
+            Some(stream) => {
+                let (lower, upper) = stream.size_hint();
+                (
+                    lower.saturating_add(buffered),
+                    upper.and_then(|upper| upper.checked_add(buffered)),
+                )
+            }
+            None => (buffered, None),
+        }
     }
 }
 
-impl<T, C, I, I2, E> Sink<I> for ReconnectStream<T, C, I2, E>
+impl<T, C, I, E> ReconnectStream<T, C, I, E>
 where
-    T: UnderlyingStream<C, I2, E>,
-    T::Stream: Sink<I, Error = E>,
+    T: UnderlyingStream<C, I, E>,
     C: Clone + Send + Unpin + 'static,
-    I2: Unpin,
-    E: Error + Unpin,
+    I: Unpin,
+    E: Error + Unpin + 'static,
 {
-    type Error = E;
-
-    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    fn poll_ready_unnamed<X>(&mut self, cx: &mut Context) -> Poll<Result<(), ReconnectError<E>>>
+    where
+        T::Stream: Sink<X, Error = E>,
+        X: Send + 'static,
+    {
+        self.register_write_waker(cx);
+        self.poll_reconnect(cx);
+        let initial_policy = self.initial_connect_send_policy();
         match self.status {
             Status::Connected => {
-                let poll = Pin::new(&mut self.stream).poll_ready(cx);
+                ready!(self.poll_drain_outbound::<X>(cx))?;
+                let poll = Pin::new(self.stream.as_mut().unwrap()).poll_ready(cx);
 
-                if self.is_write_disconnect_detected(&poll) {
-                    self.on_disconnect(cx);
+                if let Some(err) = Self::write_disconnect_error(&poll) {
+                    self.on_connection_lost(cx, DisconnectKind::Write, Some(err));
                     Poll::Pending
                 } else {
-                    poll
+                    poll.map_err(ReconnectError::Underlying)
                 }
             }
-            Status::Disconnected(_) => {
-                self.poll_disconnect(cx);
+            Status::Disconnected(ref status) if status.circuit_open => {
+                Poll::Ready(Err(ReconnectError::CircuitOpen))
+            }
+            _ if initial_policy == Some(InitialConnectSendPolicy::Buffer) => {
+                self.poll_outbound_room()
+            }
+            _ if initial_policy == Some(InitialConnectSendPolicy::Error) => {
+                Poll::Ready(Err(ReconnectError::Disconnected))
+            }
+            _ if initial_policy == Some(InitialConnectSendPolicy::Block) => Poll::Pending,
+            Status::Disconnected(_) | Status::Paused { .. } if self.options.fail_fast_send() => {
+                Poll::Ready(Err(ReconnectError::Disconnected))
+            }
+            Status::Disconnected(_) | Status::Paused { .. }
+                if self.options.outbound_buffer_capacity().is_some() =>
+            {
+                // items are buffered by `start_send` until reconnected
+                self.poll_outbound_room()
+            }
+            Status::Disconnected(_) | Status::Paused { .. } => Poll::Pending,
+            Status::FailedAndExhausted => Poll::Ready(Err(ReconnectError::ReconnectsExhausted)),
+            Status::Shutdown => Poll::Ready(Err(ReconnectError::Shutdown)),
+        }
+    }
+
+    fn start_send_unnamed<X>(&mut self, item: X) -> Result<(), ReconnectError<E>>
+    where
+        T::Stream: Sink<X, Error = E>,
+        X: Send + 'static,
+    {
+        let initial_policy = self.initial_connect_send_policy();
+        match (&self.status, self.options.outbound_buffer_capacity()) {
+            (Status::Disconnected(status), _) if status.circuit_open => {
+                Err(ReconnectError::CircuitOpen)
+            }
+            (_, capacity) if initial_policy == Some(InitialConnectSendPolicy::Buffer) => {
+                self.buffer_outbound(item, capacity.unwrap_or(usize::MAX))
+            }
+            _ if initial_policy.is_some() => Err(ReconnectError::Disconnected),
+            (Status::Disconnected(_) | Status::Paused { .. }, _)
+                if self.options.fail_fast_send() =>
+            {
+                Err(ReconnectError::Disconnected)
+            }
+            (Status::Disconnected(_) | Status::Paused { .. }, Some(capacity)) => {
+                self.buffer_outbound(item, capacity)
+            }
+            // the connection was lost since `poll_ready`, e.g. by the other half of a split stream
+            (Status::Disconnected(_) | Status::Paused { .. }, None) => {
+                Err(ReconnectError::Disconnected)
+            }
+            (Status::FailedAndExhausted, _) => Err(ReconnectError::ReconnectsExhausted),
+            (Status::Shutdown, _) => Err(ReconnectError::Shutdown),
+            (Status::Connected, _) => self.start_send_underlying(item),
+        }
+    }
+
+    /// Returns the send policy of the initial connect while it is in progress.
+    fn initial_connect_send_policy(&self) -> Option<InitialConnectSendPolicy> {
+        match self.status {
+            Status::Disconnected(_) | Status::Paused { .. } if self.last_connected_at.is_none() => {
+                self.options.initial_connect_send_policy()
+            }
+            _ => None,
+        }
+    }
+
+    /// Waits for room in the outbound buffer if it is full and the overflow policy blocks.
+    fn poll_outbound_room(&self) -> Poll<Result<(), ReconnectError<E>>> {
+        match self.options.outbound_buffer_capacity() {
+            Some(capacity)
+                if self.outbound.len() >= capacity
+                    && self.options.outbound_overflow_policy() == OutboundOverflowPolicy::Block =>
+            {
+                // woken up once reconnected
                 Poll::Pending
             }
-            Status::FailedAndExhausted => Poll::Ready(Err(T::exhaust_err())),
+            _ => Poll::Ready(Ok(())),
         }
     }
 
-    fn start_send(mut self: Pin<&mut Self>, item: I) -> Result<(), Self::Error> {
-        Pin::new(&mut self.stream).start_send(item)
+    /// Keeps an item sent while disconnected until reconnected,
+    /// following the overflow policy if the outbound buffer is full.
+    fn buffer_outbound<X: Send + 'static>(
+        &mut self,
+        item: X,
+        capacity: usize,
+    ) -> Result<(), ReconnectError<E>> {
+        if self.outbound.front().is_some_and(|item| !item.is::<X>()) {
+            return Err(ReconnectError::ItemTypeMismatch);
+        }
+        if self.outbound.len() >= capacity {
+            self.stats.dropped_outbound += 1;
+            match self.options.outbound_overflow_policy() {
+                OutboundOverflowPolicy::DropOldest => {
+                    debug!("Outbound buffer is full, dropping the oldest item.");
+                    self.outbound.pop_front();
+                }
+                OutboundOverflowPolicy::RejectNew | OutboundOverflowPolicy::Block => {
+                    return Err(ReconnectError::OutboundBufferFull)
+                }
+            }
+        }
+        self.outbound.push_back(Box::new(item));
+        Ok(())
     }
 
-    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    fn poll_flush_unnamed<X>(&mut self, cx: &mut Context) -> Poll<Result<(), ReconnectError<E>>>
+    where
+        T::Stream: Sink<X, Error = E>,
+        X: Send + 'static,
+    {
+        self.register_write_waker(cx);
+        self.poll_reconnect(cx);
+        let initial_policy = self.initial_connect_send_policy();
         match self.status {
             Status::Connected => {
-                let poll = Pin::new(&mut self.stream).poll_flush(cx);
+                ready!(self.poll_drain_outbound::<X>(cx))?;
+                let poll = Pin::new(self.stream.as_mut().unwrap()).poll_flush(cx);
 
-                if self.is_write_disconnect_detected(&poll) {
-                    self.on_disconnect(cx);
+                if let Some(err) = Self::write_disconnect_error(&poll) {
+                    self.on_connection_lost(cx, DisconnectKind::Write, Some(err));
+                    Poll::Pending
+                } else if self.flush_timed_out(cx, &poll) {
+                    self.on_connection_lost(cx, DisconnectKind::FlushTimeout, None);
                     Poll::Pending
                 } else {
-                    poll
+                    if let Poll::Ready(Ok(())) = poll {
+                        self.unflushed.clear();
+                    }
+                    poll.map_err(ReconnectError::Underlying)
                 }
             }
-            Status::Disconnected(_) => {
-                self.poll_disconnect(cx);
-                Poll::Pending
+            Status::Disconnected(ref status) if status.circuit_open => {
+                Poll::Ready(Err(ReconnectError::CircuitOpen))
+            }
+            _ if initial_policy == Some(InitialConnectSendPolicy::Error) => {
+                Poll::Ready(Err(ReconnectError::Disconnected))
             }
-            Status::FailedAndExhausted => Poll::Ready(Err(T::exhaust_err())),
+            _ if initial_policy.is_some() => Poll::Pending,
+            Status::Disconnected(_) | Status::Paused { .. } if self.options.fail_fast_send() => {
+                Poll::Ready(Err(ReconnectError::Disconnected))
+            }
+            Status::Disconnected(_) | Status::Paused { .. } => Poll::Pending,
+            Status::FailedAndExhausted => Poll::Ready(Err(ReconnectError::ReconnectsExhausted)),
+            Status::Shutdown => Poll::Ready(Err(ReconnectError::Shutdown)),
         }
     }
 
-    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    fn poll_close_unnamed<X>(&mut self, cx: &mut Context) -> Poll<Result<(), ReconnectError<E>>>
+    where
+        T::Stream: Sink<X, Error = E>,
+        X: Send + 'static,
+    {
+        self.register_write_waker(cx);
         match self.status {
             Status::Connected => {
-                let poll = Pin::new(&mut self.stream).poll_close(cx);
-                if poll.is_ready() {
-                    // if completed, we are disconnected whether error or not
-                    self.on_disconnect(cx);
+                ready!(self.poll_drain_outbound::<X>(cx))?;
+                let result = ready!(Pin::new(self.stream.as_mut().unwrap()).poll_close(cx));
+                if result.is_ok() {
+                    self.unflushed.clear();
                 }
+                info!("Closed, no longer reconnecting.");
+                self.shut_down();
+                Poll::Ready(result.map_err(ReconnectError::Underlying))
+            }
+            Status::Disconnected(_) | Status::Paused { .. } => {
+                info!("Closed while disconnected, no longer reconnecting.");
+                self.shut_down();
+                Poll::Ready(Ok(()))
+            }
+            Status::FailedAndExhausted => Poll::Ready(Err(ReconnectError::ReconnectsExhausted)),
+            Status::Shutdown => Poll::Ready(Ok(())),
+        }
+    }
 
-                poll
+    /// Points this stream at `new_ctor_arg` for a planned cutover, such as a blue/green deploy:
+    /// a connection to it is established first, then this stream switches over to it,
+    /// and only then the old connection is flushed and closed.
+    /// `X` is the item type sent through the [Sink], as the old connection is flushed before it is closed.
+    ///
+    /// Unlike [force_reconnect](Self::force_reconnect), the old connection stays live until the new one is ready,
+    /// and the buffered items are kept: the outbound ones are sent to the new connection, along with the
+    /// items the old one failed to flush if [with_resend_unflushed](ReconnectOptions::with_resend_unflushed) is enabled.
+    /// The old connection is not read while the new one is established, and what it did not deliver yet is lost.
+    /// The new ctor arg replaces the current one, so it is also used to reconnect from then on.
+    ///
+    /// If the connect attempt fails, nothing changes and its error is returned. While reconnecting or paused,
+    /// the new connection ends the reconnection. Fails if the stream gave up reconnecting, with
+    /// [ReconnectsExhausted](ReconnectError::ReconnectsExhausted) or [Shutdown](ReconnectError::Shutdown).
+    pub async fn migrate<X>(&mut self, new_ctor_arg: C) -> Result<(), ReconnectError<E>>
+    where
+        T::Stream: Sink<X, Error = E>,
+        X: Send + 'static,
+    {
+        let result = self.migrate_unnamed::<X>(new_ctor_arg).await;
+        result.map_err(|err| err.named(self.options.name()))
+    }
+
+    async fn migrate_unnamed<X>(&mut self, new_ctor_arg: C) -> Result<(), ReconnectError<E>>
+    where
+        T::Stream: Sink<X, Error = E>,
+        X: Send + 'static,
+    {
+        match self.status {
+            Status::FailedAndExhausted => return Err(ReconnectError::ReconnectsExhausted),
+            Status::Shutdown => return Err(ReconnectError::Shutdown),
+            _ => {}
+        }
+        info!("Migrating to a new endpoint");
+        let clock = self.options.clock().clone();
+        let connect_timeout = self.options.connect_timeout(0);
+        let wait = self.rate_limiter.schedule(&self.options, Duration::ZERO);
+        if wait > Duration::ZERO {
+            clock.sleep(wait).await;
+        }
+        let stream =
+            match Self::establish_within(&clock, connect_timeout, new_ctor_arg.clone()).await {
+                Ok(stream) => stream,
+                Err(err) => {
+                    Self::report_connect_error(&self.options, &err);
+                    return Err(err.into_error());
+                }
+            };
+        self.endpoints.ctor_args[self.endpoints.current] = new_ctor_arg;
+
+        let reconnected = !matches!(self.status, Status::Connected);
+        let old_stream = self.stream.take();
+        self.stop(Status::Connected);
+        self.paused = false;
+        self.previous_attempts = None;
+
+        info!("Migrated to the new endpoint");
+        // the new connection is in place before awaiting the old one, so that the stream is consistent
+        // even if this future is dropped meanwhile
+        let now = clock.now();
+        self.stream = Some(stream);
+        self.stats.connection = ConnectionStats::default();
+        self.connected_at = now;
+        self.last_connected_at = Some(now);
+        self.last_read_at = now;
+        self.read_errors = 0;
+        self.drained_unflushed = 0;
+        self.idle_timer = None;
+        self.flush_timer = None;
+        if reconnected {
+            self.stats.reconnects += 1;
+            self.emit(ConnEvent::Connected);
+        }
+        self.wake_all();
+
+        if let Some(mut old_stream) = old_stream {
+            // resent to the new connection unless the old one confirms them flushed
+            let unflushed = self.unflushed.len();
+            while let Some(item) = self.unflushed.pop_back() {
+                self.outbound.push_front(item);
+            }
+            match future::poll_fn(|cx| Pin::new(&mut old_stream).poll_flush(cx)).await {
+                Ok(()) => {
+                    self.outbound.drain(..unflushed);
+                }
+                Err(err) => warn!("Flushing the old connection failed: {:?}.", err),
+            }
+            if let Err(err) = future::poll_fn(|cx| Pin::new(&mut old_stream).poll_close(cx)).await {
+                warn!("Closing the old connection failed: {:?}.", err);
             }
-            Status::Disconnected(_) => Poll::Pending,
-            Status::FailedAndExhausted => Poll::Ready(Err(T::exhaust_err())),
         }
+        Ok(())
+    }
+}
+
+impl<T, C, I, I2, E> Sink<I> for ReconnectStream<T, C, I2, E>
+where
+    T: UnderlyingStream<C, I2, E>,
+    T::Stream: Sink<I, Error = E>,
+    C: Clone + Send + Unpin + 'static,
+    I: Send + 'static,
+    I2: Unpin,
+    E: Error + Unpin + 'static,
+{
+    type Error = ReconnectError<E>;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let poll = self.poll_ready_unnamed::<I>(cx);
+        poll.map_err(|err| err.named(self.options.name()))
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: I) -> Result<(), Self::Error> {
+        let result = self.start_send_unnamed(item);
+        result.map_err(|err| err.named(self.options.name()))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let poll = self.poll_flush_unnamed::<I>(cx);
+        poll.map_err(|err| err.named(self.options.name()))
+    }
+
+    /// Closes the stream for good: buffered items are sent and the underlying sink is closed,
+    /// then the stream is shut down instead of reconnecting, as if the
+    /// [shutdown signal](ReconnectOptions::with_shutdown_signal) completed.
+    /// An error closing the underlying sink is returned, and the stream is shut down all the same.
+    ///
+    /// If the connection is lost, including while sending the buffered items, the stream is shut down
+    /// right away, and the items which were not sent can be recovered with [take_pending](ReconnectStream::take_pending).
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let poll = self.poll_close_unnamed::<I>(cx);
+        poll.map_err(|err| err.named(self.options.name()))
     }
 }
@@ -0,0 +1,305 @@
+//! Provides a scriptable [UnderlyingStream] to test code built on top of [ReconnectStream]
+//! without a real connection.
+//!
+//! A [MockScript] is a queue of [MockStep]s shared by every connection the mock establishes,
+//! so a single script can describe a whole session, reconnects included.
+//! It is passed as the ctor arg of the [ReconnectStream], and can be inspected or extended
+//! through a clone of it while the stream is in use.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! use std::io;
+//! use std::time::Duration;
+//! use futures::StreamExt;
+//! use stream_reconnect::testing::{MockScript, MockStep, ReconnectMock};
+//! use stream_reconnect::ReconnectOptions;
+//!
+//! // Yield two items, then disconnect, then fail to connect twice, then succeed.
+//! let script = MockScript::new(vec![
+//!     MockStep::Item(1),
+//!     MockStep::Item(2),
+//!     MockStep::Disconnect,
+//!     MockStep::FailConnect(io::Error::other("refused")),
+//!     MockStep::FailConnect(io::Error::other("refused")),
+//!     MockStep::Item(3),
+//! ]);
+//!
+//! let options = ReconnectOptions::new()
+//!     .with_retries_generator(|| vec![Duration::from_millis(1); 3]);
+//! let mut stream = ReconnectMock::connect_with_options(script.clone(), options)
+//!     .await
+//!     .unwrap();
+//!
+//! let items: Vec<i32> = stream.by_ref().take(3).collect().await;
+//! assert_eq!(items, vec![1, 2, 3]);
+//! assert_eq!(script.connects(), 2);
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::error::Error;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+
+use futures::{Sink, Stream};
+
+use crate::{ReconnectStream, UnderlyingStream};
+
+/// A [ReconnectStream] over a [MockStream].
+pub type ReconnectMock<I, E> = ReconnectStream<MockStream<I, E>, MockScript<I, E>, I, E>;
+
+/// A step of a [MockScript]. Steps are consumed in order.
+#[derive(Debug)]
+pub enum MockStep<I, E> {
+    /// The stream yields the item.
+    Item(I),
+    /// The stream ends, as if the peer closed the connection.
+    Disconnect,
+    /// The next connect attempt fails with the error.
+    FailConnect(E),
+    /// The next write fails with the error, which is considered a disconnect.
+    FailWrite(E),
+}
+
+struct State<I, E> {
+    steps: VecDeque<MockStep<I, E>>,
+    sent: Vec<I>,
+    connects: usize,
+    waker: Option<Waker>,
+}
+
+/// The script driving a [MockStream]. Cloning it yields a handle to the same script.
+pub struct MockScript<I, E>(Arc<Mutex<State<I, E>>>);
+
+impl<I, E> Clone for MockScript<I, E> {
+    fn clone(&self) -> Self {
+        MockScript(self.0.clone())
+    }
+}
+
+impl<I, E> MockScript<I, E> {
+    pub fn new(steps: impl IntoIterator<Item = MockStep<I, E>>) -> Self {
+        MockScript(Arc::new(Mutex::new(State {
+            steps: steps.into_iter().collect(),
+            sent: Vec::new(),
+            connects: 0,
+            waker: None,
+        })))
+    }
+
+    /// Appends a step to the script, waking up the stream if it waits for one.
+    pub fn push(&self, step: MockStep<I, E>) {
+        let mut state = self.state();
+        state.steps.push_back(step);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+
+    /// Returns true if every step of the script was consumed.
+    pub fn is_done(&self) -> bool {
+        self.state().steps.is_empty()
+    }
+
+    /// Returns the number of connections established so far.
+    pub fn connects(&self) -> usize {
+        self.state().connects
+    }
+
+    /// Returns the items sent on any connection so far.
+    pub fn sent(&self) -> Vec<I>
+    where
+        I: Clone,
+    {
+        self.state().sent.clone()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State<I, E>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A connection established from a [MockScript].
+///
+/// Once the connection is lost by a [Disconnect](MockStep::Disconnect) or a
+/// [FailWrite](MockStep::FailWrite) step, it stays closed, and the next steps are left for the next connection.
+/// Reads wait while the next step is meant for a write or a connect attempt, or when the script is exhausted.
+pub struct MockStream<I, E> {
+    script: MockScript<I, E>,
+    closed: bool,
+}
+
+impl<I, E> UnderlyingStream<MockScript<I, E>, I, E> for MockStream<I, E>
+where
+    I: Send + 'static,
+    E: Error + Send + 'static,
+{
+    type Stream = Self;
+
+    async fn establish(script: MockScript<I, E>) -> Result<Self, E> {
+        {
+            let mut state = script.state();
+            if let Some(MockStep::FailConnect(_)) = state.steps.front() {
+                if let Some(MockStep::FailConnect(err)) = state.steps.pop_front() {
+                    return Err(err);
+                }
+            }
+            state.connects += 1;
+        }
+        Ok(MockStream {
+            script,
+            closed: false,
+        })
+    }
+
+    fn is_write_disconnect_error(_: &E) -> bool {
+        true
+    }
+}
+
+impl<I, E> Stream for MockStream<I, E> {
+    type Item = I;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.closed {
+            return Poll::Ready(None);
+        }
+        let mut state = self.script.state();
+        match state.steps.front() {
+            Some(MockStep::Item(_)) => match state.steps.pop_front() {
+                Some(MockStep::Item(item)) => Poll::Ready(Some(item)),
+                _ => unreachable!(),
+            },
+            Some(MockStep::Disconnect) => {
+                state.steps.pop_front();
+                drop(state);
+                self.closed = true;
+                Poll::Ready(None)
+            }
+            _ => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<I, E> MockStream<I, E> {
+    fn poll_write(&mut self) -> Poll<Result<(), E>> {
+        let mut state = self.script.state();
+        if let Some(MockStep::FailWrite(_)) = state.steps.front() {
+            if let Some(MockStep::FailWrite(err)) = state.steps.pop_front() {
+                drop(state);
+                self.closed = true;
+                return Poll::Ready(Err(err));
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<I, E> Sink<I> for MockStream<I, E> {
+    type Error = E;
+
+    fn poll_ready(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.poll_write()
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: I) -> Result<(), Self::Error> {
+        self.script.state().sent.push(item);
+        Ok(())
+    }
+
+    fn poll_flush(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.poll_write()
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io;
+    use std::time::Duration;
+
+    use futures::{SinkExt, StreamExt};
+
+    use super::{MockScript, MockStep, ReconnectMock};
+    use crate::{ConnectionStatus, ReconnectOptions};
+
+    fn options() -> ReconnectOptions {
+        ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(1); 3])
+    }
+
+    #[tokio::test]
+    async fn test_reconnects_through_failed_connects() {
+        let script = MockScript::new(vec![
+            MockStep::Item(1),
+            MockStep::Disconnect,
+            MockStep::FailConnect(io::Error::other("refused")),
+            MockStep::FailConnect(io::Error::other("refused")),
+            MockStep::Item(2),
+        ]);
+        let mut stream = ReconnectMock::connect_with_options(script.clone(), options())
+            .await
+            .unwrap();
+
+        assert_eq!(stream.next().await, Some(1));
+        assert_eq!(stream.next().await, Some(2));
+        assert_eq!(stream.stats().failed_attempts, 2);
+        assert_eq!(script.connects(), 2);
+        assert!(script.is_done());
+    }
+
+    #[tokio::test]
+    async fn test_reconnects_on_write_failure() {
+        let script = MockScript::new(vec![MockStep::FailWrite(io::Error::other("reset"))]);
+        let mut stream = ReconnectMock::connect_with_options(script.clone(), options())
+            .await
+            .unwrap();
+
+        stream.send(1).await.unwrap();
+        assert_eq!(stream.status(), ConnectionStatus::Connected);
+        assert_eq!(script.connects(), 2);
+        assert_eq!(script.sent(), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn test_buffers_items_which_are_not_clone() {
+        struct Payload;
+
+        let script = MockScript::new(vec![MockStep::<Payload, io::Error>::Disconnect]);
+        let mut stream =
+            ReconnectMock::connect_with_options(script.clone(), options().with_outbound_buffer(1))
+                .await
+                .unwrap();
+
+        assert!(futures::poll!(stream.next()).is_pending());
+        stream.feed(Payload).await.unwrap();
+        stream.flush().await.unwrap();
+        assert_eq!(script.connects(), 2);
+        assert_eq!(script.state().sent.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_wakes_up_on_pushed_step() {
+        let script: MockScript<i32, io::Error> = MockScript::new(vec![]);
+        let mut stream = ReconnectMock::connect(script.clone()).await.unwrap();
+
+        let handle = script.clone();
+        tokio::spawn(async move { handle.push(MockStep::Item(1)) });
+        assert_eq!(stream.next().await, Some(1));
+    }
+}
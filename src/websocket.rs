@@ -0,0 +1,248 @@
+//! Integration with WebSocket streams built on [tungstenite](https://docs.rs/tungstenite),
+//! such as the ones of `tokio-tungstenite` and `async-tungstenite`.
+//!
+//! With the `tokio` runtime, [ReconnectWebSocket] is ready to use over `tokio-tungstenite`:
+//!
+//! ```rust,no_run
+//! use futures::SinkExt;
+//! use stream_reconnect::websocket::ReconnectWebSocket;
+//! use tokio_tungstenite::tungstenite::Message;
+//!
+//! # async fn run() {
+//! let mut ws = ReconnectWebSocket::connect(String::from("ws://localhost:8000"))
+//!     .await
+//!     .unwrap();
+//! ws.send(Message::text("hello world!")).await.unwrap();
+//! # }
+//! ```
+//!
+//! Other WebSocket streams need their own [UnderlyingStream] implementation.
+//! A WebSocket peer closes the connection with a close frame, whose code tells whether
+//! it was on purpose. Delegating [classify_item](crate::UnderlyingStream::classify_item) to
+//! [classify_close] ends the stream on a normal close and reconnects on an abnormal one,
+//! as decided by [should_reconnect_on_close](crate::UnderlyingStream::should_reconnect_on_close).
+//!
+//! ```rust,no_run
+//! use std::future::Future;
+//! use stream_reconnect::{ItemAction, UnderlyingStream};
+//! use tokio::net::TcpStream;
+//! use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+//! use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+//! use tokio_tungstenite::tungstenite::{error::Error as WsError, Message};
+//! use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+//!
+//! struct MyWs;
+//!
+//! # #[cfg(not(feature = "not-send"))]
+//! impl UnderlyingStream<String, Result<Message, WsError>, WsError> for MyWs {
+//!     type Stream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+//!
+//!     fn establish(addr: String) -> impl Future<Output = Result<Self::Stream, WsError>> + Send {
+//!         async move { Ok(connect_async(addr).await?.0) }
+//!     }
+//!
+//!     fn is_write_disconnect_error(err: &WsError) -> bool {
+//!         matches!(err, WsError::ConnectionClosed | WsError::AlreadyClosed | WsError::Io(_))
+//!     }
+//!
+//!     fn is_read_disconnect_error(item: &Result<Message, WsError>) -> bool {
+//!         matches!(item, Err(err) if Self::is_write_disconnect_error(err))
+//!     }
+//!
+//!     fn classify_item(item: Result<Message, WsError>) -> ItemAction<Result<Message, WsError>> {
+//!         stream_reconnect::websocket::classify_close::<Self, _, _>(item)
+//!     }
+//!
+//!     // the server going away for a restart is worth reconnecting, unlike a normal close
+//!     fn should_reconnect_on_close(frame: &CloseFrame<'_>) -> bool {
+//!         matches!(frame.code, CloseCode::Away | CloseCode::Abnormal | CloseCode::Error)
+//!     }
+//! }
+//! ```
+
+use std::error::Error;
+
+#[cfg(feature = "tokio")]
+use tokio::net::TcpStream;
+#[cfg(feature = "tokio")]
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+use tungstenite::protocol::frame::coding::CloseCode;
+use tungstenite::protocol::CloseFrame;
+use tungstenite::Message;
+
+#[cfg(feature = "tokio")]
+use crate::ReconnectStream;
+use crate::{ItemAction, UnderlyingStream};
+
+/// A `tokio-tungstenite` WebSocket client which reconnects to the URL it was connected to.
+#[cfg(feature = "tokio")]
+pub type ReconnectWebSocket = ReconnectStream<
+    TokioTungstenite,
+    String,
+    Result<Message, tungstenite::Error>,
+    tungstenite::Error,
+>;
+
+/// The [UnderlyingStream] of [ReconnectWebSocket], which connects to the URL given as ctor arg
+/// with `tokio_tungstenite::connect_async`.
+///
+/// A closed connection, an I/O error or a protocol error, such as the connection being reset
+/// without a closing handshake, triggers a reconnect. Close frames are handled by [classify_close].
+/// An invalid URL, or a handshake rejected with 401 or 403, makes the stream give up.
+#[cfg(feature = "tokio")]
+#[derive(Debug)]
+pub struct TokioTungstenite;
+
+#[cfg(feature = "tokio")]
+impl UnderlyingStream<String, Result<Message, tungstenite::Error>, tungstenite::Error>
+    for TokioTungstenite
+{
+    type Stream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+    async fn establish(url: String) -> Result<Self::Stream, tungstenite::Error> {
+        Ok(connect_async(url).await?.0)
+    }
+
+    fn is_write_disconnect_error(err: &tungstenite::Error) -> bool {
+        matches!(
+            err,
+            tungstenite::Error::ConnectionClosed
+                | tungstenite::Error::AlreadyClosed
+                | tungstenite::Error::Io(_)
+                | tungstenite::Error::Protocol(_)
+        )
+    }
+
+    fn is_read_disconnect_error(item: &Result<Message, tungstenite::Error>) -> bool {
+        matches!(item, Err(err) if Self::is_write_disconnect_error(err))
+    }
+
+    fn read_disconnect_error(
+        item: &Result<Message, tungstenite::Error>,
+    ) -> Option<&tungstenite::Error> {
+        item.as_ref().err()
+    }
+
+    /// An invalid URL, or a handshake rejected as unauthorized, is not going to succeed later on.
+    fn is_fatal_establish_error(err: &tungstenite::Error) -> bool {
+        match err {
+            tungstenite::Error::Url(_) => true,
+            tungstenite::Error::Http(response) => matches!(response.status().as_u16(), 401 | 403),
+            _ => false,
+        }
+    }
+
+    fn classify_item(
+        item: Result<Message, tungstenite::Error>,
+    ) -> ItemAction<Result<Message, tungstenite::Error>> {
+        classify_close::<Self, _, _>(item)
+    }
+}
+
+/// Classifies a read WebSocket message, asking [UnderlyingStream::should_reconnect_on_close] what to do
+/// upon a close frame: it is either dropped in favor of a reconnect, or it ends the stream.
+/// A close message without a frame is handled as a frame with the "no status" code (1005).
+/// Any other message or error is yielded.
+pub fn classify_close<T, C, E>(
+    item: Result<Message, tungstenite::Error>,
+) -> ItemAction<Result<Message, tungstenite::Error>>
+where
+    T: UnderlyingStream<C, Result<Message, tungstenite::Error>, E>,
+    C: Clone + Send + Unpin,
+    E: Error,
+{
+    let reconnect = match &item {
+        Ok(Message::Close(Some(frame))) => T::should_reconnect_on_close(frame),
+        Ok(Message::Close(None)) => T::should_reconnect_on_close(&CloseFrame {
+            code: CloseCode::Status,
+            reason: "".into(),
+        }),
+        _ => return ItemAction::Yield(item),
+    };
+    if reconnect {
+        ItemAction::Disconnect
+    } else {
+        ItemAction::Terminate
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::future::{self, Future};
+    use std::io;
+
+    use tungstenite::protocol::frame::coding::CloseCode;
+    use tungstenite::protocol::CloseFrame;
+    use tungstenite::Message;
+
+    use super::classify_close;
+    use crate::{ItemAction, UnderlyingStream};
+
+    struct Ws;
+
+    impl UnderlyingStream<(), Result<Message, tungstenite::Error>, io::Error> for Ws {
+        type Stream = ();
+
+        fn establish(_: ()) -> impl Future<Output = Result<(), io::Error>> + Send {
+            future::pending()
+        }
+
+        fn is_write_disconnect_error(_: &io::Error) -> bool {
+            true
+        }
+    }
+
+    fn close(code: CloseCode) -> Message {
+        Message::Close(Some(CloseFrame {
+            code,
+            reason: "".into(),
+        }))
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn test_tokio_tungstenite_disconnect_errors() {
+        use tungstenite::error::{Error, ProtocolError, UrlError};
+
+        use super::TokioTungstenite;
+
+        assert!(TokioTungstenite::is_write_disconnect_error(
+            &Error::ConnectionClosed
+        ));
+        assert!(TokioTungstenite::is_read_disconnect_error(&Err(
+            Error::Protocol(ProtocolError::ResetWithoutClosingHandshake)
+        )));
+        assert!(!TokioTungstenite::is_read_disconnect_error(&Ok(
+            Message::text("a")
+        )));
+        assert!(!TokioTungstenite::is_write_disconnect_error(&Error::Utf8));
+        assert!(TokioTungstenite::is_fatal_establish_error(&Error::Url(
+            UrlError::NoHostName
+        )));
+    }
+
+    #[test]
+    fn test_classify_close_codes() {
+        let classify = classify_close::<Ws, _, _>;
+        assert!(matches!(
+            classify(Ok(close(CloseCode::Normal))),
+            ItemAction::Terminate
+        ));
+        assert!(matches!(
+            classify(Ok(close(CloseCode::Abnormal))),
+            ItemAction::Disconnect
+        ));
+        assert!(matches!(
+            classify(Ok(close(CloseCode::Error))),
+            ItemAction::Disconnect
+        ));
+        assert!(matches!(
+            classify(Ok(Message::Close(None))),
+            ItemAction::Disconnect
+        ));
+        assert!(matches!(
+            classify(Ok(Message::text("a"))),
+            ItemAction::Yield(Ok(Message::Text(_)))
+        ));
+    }
+}
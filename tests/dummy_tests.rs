@@ -9,23 +9,56 @@ use std::time::Duration;
 use futures::{Sink, Stream};
 
 use stream_reconnect::ReconnectOptions;
-use stream_reconnect::{ReconnectStream, UnderlyingStream};
+use stream_reconnect::{ItemAction, ReconnectStream, UnderlyingStream};
 
 #[derive(Default)]
 pub struct DummyStream {
     poll_read_results: PollReadResults,
+    sent_items: SentItems,
+    flush_results: FlushResults,
+    slow_flush: bool,
+    flush_stalled: bool,
+    stuck_flush: bool,
+    unflushed_at_send: UnflushedAtSend,
+    flushed: usize,
+    clean_eof: bool,
 }
 
 #[derive(Default, Clone)]
 struct DummyCtor {
+    endpoint: u8,
+    attempted_endpoints: AttemptedEndpoints,
     connect_outcomes: ConnectOutcomes,
+    connect_error_kinds: ConnectErrorKinds,
+    connect_delays: ConnectDelays,
     poll_read_results: PollReadResults,
+    sent_items: SentItems,
+    flush_results: FlushResults,
+    slow_flush: bool,
+    stuck_flushes: StuckFlushes,
+    unflushed_at_send: UnflushedAtSend,
+    clean_eof: bool,
 }
 
 type ConnectOutcomes = Arc<Mutex<Vec<bool>>>;
 
+type ConnectErrorKinds = Arc<Mutex<Vec<ErrorKind>>>;
+
+type ConnectDelays = Arc<Mutex<Vec<Duration>>>;
+
+type AttemptedEndpoints = Arc<Mutex<Vec<u8>>>;
+
 type PollReadResults = Arc<Mutex<Vec<(Poll<io::Result<()>>, Vec<u8>)>>>;
 
+type SentItems = Arc<Mutex<Vec<Vec<u8>>>>;
+
+type FlushResults = Arc<Mutex<Vec<io::Result<()>>>>;
+
+type UnflushedAtSend = Arc<Mutex<Vec<usize>>>;
+
+/// Number of connections left whose flushes never complete
+type StuckFlushes = Arc<Mutex<usize>>;
+
 struct DummyStreamConnector;
 
 impl UnderlyingStream<DummyCtor, Vec<u8>, io::Error> for DummyStreamConnector {
@@ -33,17 +66,45 @@ impl UnderlyingStream<DummyCtor, Vec<u8>, io::Error> for DummyStreamConnector {
 
     #[cfg(not(feature = "not-send"))]
     async fn establish(ctor: DummyCtor) -> io::Result<DummyStream> {
+        ctor.attempted_endpoints.lock().unwrap().push(ctor.endpoint);
+        let delay = {
+            let mut connect_delays = ctor.connect_delays.lock().unwrap();
+            (!connect_delays.is_empty()).then(|| connect_delays.remove(0))
+        };
+        if let Some(delay) = delay {
+            tokio::time::sleep(delay).await;
+        }
+
         let mut connect_attempt_outcome_results = ctor.connect_outcomes.lock().unwrap();
 
         let should_succeed = connect_attempt_outcome_results.remove(0);
         if should_succeed {
+            let stuck_flush = {
+                let mut stuck_flushes = ctor.stuck_flushes.lock().unwrap();
+                let stuck = *stuck_flushes > 0;
+                *stuck_flushes = stuck_flushes.saturating_sub(1);
+                stuck
+            };
             let dummy_io = DummyStream {
                 poll_read_results: ctor.poll_read_results.clone(),
+                sent_items: ctor.sent_items.clone(),
+                flush_results: ctor.flush_results.clone(),
+                slow_flush: ctor.slow_flush,
+                stuck_flush,
+                unflushed_at_send: ctor.unflushed_at_send.clone(),
+                clean_eof: ctor.clean_eof,
+                ..DummyStream::default()
             };
 
             Ok(dummy_io)
         } else {
-            Err(io::Error::new(ErrorKind::NotConnected, "So unfortunate"))
+            let mut connect_error_kinds = ctor.connect_error_kinds.lock().unwrap();
+            let kind = if connect_error_kinds.is_empty() {
+                ErrorKind::NotConnected
+            } else {
+                connect_error_kinds.remove(0)
+            };
+            Err(io::Error::new(kind, "So unfortunate"))
         }
     }
 
@@ -64,12 +125,22 @@ impl UnderlyingStream<DummyCtor, Vec<u8>, io::Error> for DummyStreamConnector {
                 | AlreadyExists
         )
     }
-
-    fn exhaust_err() -> Error {
-        io::Error::new(
-            ErrorKind::NotConnected,
-            "Disconnected. Connection attempts have been exhausted.",
-        )
+    fn is_fatal_establish_error(err: &Error) -> bool {
+        err.kind() == ErrorKind::PermissionDenied
+    }
+    fn is_clean_eof(stream: &DummyStream) -> bool {
+        stream.clean_eof
+    }
+    fn is_read_disconnect_error(item: &Vec<u8>) -> bool {
+        item == b"err"
+    }
+    fn classify_item(item: Vec<u8>) -> ItemAction<Vec<u8>> {
+        match item.as_slice() {
+            b"bye" => ItemAction::Disconnect,
+            b"ping" => ItemAction::Drop,
+            b"close" => ItemAction::Terminate,
+            _ => ItemAction::Yield(item),
+        }
     }
 }
 
@@ -84,6 +155,11 @@ impl Stream for DummyStream {
 
         let (result, bytes) = poll_read_results.remove(0);
 
+        if result.is_pending() {
+            // a silent connection, nothing wakes us up
+            return Poll::Pending;
+        }
+
         if let Poll::Ready(Err(e)) = result {
             if e.kind() == io::ErrorKind::WouldBlock {
                 cx.waker().wake_by_ref();
@@ -101,15 +177,39 @@ impl Sink<Vec<u8>> for DummyStream {
     type Error = io::Error;
 
     fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        unreachable!()
+        Poll::Ready(Ok(()))
     }
 
-    fn start_send(self: Pin<&mut Self>, _item: Vec<u8>) -> Result<(), Self::Error> {
-        unreachable!()
+    fn start_send(self: Pin<&mut Self>, item: Vec<u8>) -> Result<(), Self::Error> {
+        let mut sent_items = self.sent_items.lock().unwrap();
+        self.unflushed_at_send
+            .lock()
+            .unwrap()
+            .push(sent_items.len() - self.flushed);
+        sent_items.push(item);
+        Ok(())
     }
 
-    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        unreachable!()
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        if this.stuck_flush {
+            // a half-open connection, nothing wakes us up
+            return Poll::Pending;
+        }
+        // a slow sink takes two polls to flush
+        if this.slow_flush && !this.flush_stalled {
+            this.flush_stalled = true;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        this.flush_stalled = false;
+        this.flushed = this.sent_items.lock().unwrap().len();
+        let mut flush_results = this.flush_results.lock().unwrap();
+        if flush_results.is_empty() {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Ready(flush_results.remove(0))
+        }
     }
 
     fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
@@ -172,6 +272,34 @@ pub mod instantiating {
         assert!(dummy.is_err());
     }
 
+    #[tokio::test]
+    async fn should_pass_establish_error_to_callback() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![false, true]));
+        let ctor = DummyCtor {
+            connect_outcomes,
+            ..DummyCtor::default()
+        };
+
+        let errors = Arc::new(Mutex::new(vec![]));
+        let errors_clone = errors.clone();
+
+        let options = ReconnectOptions::new()
+            .with_exit_if_first_connect_fails(false)
+            .with_retries_generator(|| vec![Duration::from_millis(10)])
+            .with_on_connect_fail_callback_err(move |err| {
+                let err = err.downcast_ref::<io::Error>().unwrap();
+                errors_clone.lock().unwrap().push(err.to_string());
+            });
+
+        let dummy = ReconnectDummy::connect_with_options(ctor, options).await;
+
+        assert!(dummy.is_ok());
+        assert_eq!(
+            *errors.lock().unwrap(),
+            vec![String::from("So unfortunate")]
+        );
+    }
+
     #[tokio::test]
     async fn should_be_connected_if_initial_connect_fails_but_then_other_succeeds() {
         let connect_outcomes = Arc::new(Mutex::new(vec![false, true]));
@@ -224,6 +352,7 @@ mod already_connected {
         let ctor = DummyCtor {
             connect_outcomes,
             poll_read_results,
+            ..DummyCtor::default()
         };
 
         let mut dummy = ReconnectDummy::connect(ctor).await.unwrap();
@@ -255,6 +384,7 @@ mod already_connected {
         let ctor = DummyCtor {
             connect_outcomes,
             poll_read_results: poll_read_results.clone(),
+            ..DummyCtor::default()
         };
 
         let disconnect_counter = Arc::new(AtomicU8::new(0));
@@ -283,6 +413,7 @@ mod already_connected {
     }
 
     #[tokio::test]
+    #[allow(clippy::io_other_error)]
     async fn should_give_up_when_all_attempts_exhausted() {
         let connect_outcomes = Arc::new(Mutex::new(vec![true, false, false, false]));
 
@@ -304,6 +435,7 @@ mod already_connected {
         let ctor = DummyCtor {
             connect_outcomes,
             poll_read_results: poll_read_results.clone(),
+            ..DummyCtor::default()
         };
 
         let options = ReconnectOptions::new().with_retries_generator(|| {
@@ -325,3 +457,4014 @@ mod already_connected {
         assert!(buf.is_empty());
     }
 }
+
+#[cfg(test)]
+mod status {
+    use futures::stream::StreamExt;
+
+    use stream_reconnect::ConnectionStatus;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_report_reconnecting_then_connected() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![true, false, true]));
+
+        let poll_read_results = Arc::new(Mutex::new(vec![
+            (
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "fatal",
+                ))),
+                vec![],
+            ),
+            (Poll::Ready(Ok(())), b"e\n".to_vec()),
+        ]));
+
+        let ctor = DummyCtor {
+            connect_outcomes,
+            poll_read_results,
+            ..DummyCtor::default()
+        };
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(50), Duration::from_millis(50)]);
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert_eq!(dummy.status(), ConnectionStatus::Connected);
+
+        assert!(futures::poll!(dummy.next()).is_pending());
+        assert_eq!(
+            dummy.status(),
+            ConnectionStatus::Reconnecting { attempt: 1 }
+        );
+
+        assert_eq!(dummy.next().await.unwrap(), b"e\n".to_vec());
+        assert_eq!(dummy.status(), ConnectionStatus::Connected);
+    }
+
+    #[tokio::test]
+    async fn should_report_next_retry_delay() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![true, true]));
+
+        let poll_read_results = Arc::new(Mutex::new(vec![
+            (
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "fatal",
+                ))),
+                vec![],
+            ),
+            (Poll::Ready(Ok(())), b"e\n".to_vec()),
+        ]));
+
+        let ctor = DummyCtor {
+            connect_outcomes,
+            poll_read_results,
+            ..DummyCtor::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(200)]);
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert_eq!(dummy.next_retry_in(), None);
+
+        assert!(futures::poll!(dummy.next()).is_pending());
+        let remaining = dummy.next_retry_in().unwrap();
+        assert!(remaining <= Duration::from_millis(200));
+        assert!(remaining > Duration::from_millis(100));
+
+        assert_eq!(dummy.next().await.unwrap(), b"e\n".to_vec());
+        assert_eq!(dummy.next_retry_in(), None);
+    }
+
+    #[tokio::test]
+    async fn should_report_dead_when_exhausted() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![true, false]));
+
+        let poll_read_results = Arc::new(Mutex::new(vec![(
+            Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "fatal",
+            ))),
+            vec![],
+        )]));
+
+        let ctor = DummyCtor {
+            connect_outcomes,
+            poll_read_results,
+            ..DummyCtor::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(10)]);
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert!(!dummy.is_dead());
+
+        assert!(dummy.next().await.is_none());
+        assert_eq!(dummy.status(), ConnectionStatus::Dead);
+        assert!(dummy.is_dead());
+    }
+}
+
+#[cfg(test)]
+mod callbacks {
+    use futures::stream::StreamExt;
+
+    use stream_reconnect::ReconnectContext;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_pass_context_to_callbacks() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![true, false, true]));
+
+        let poll_read_results = Arc::new(Mutex::new(vec![
+            (
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "fatal",
+                ))),
+                vec![],
+            ),
+            (Poll::Ready(Ok(())), b"e\n".to_vec()),
+        ]));
+
+        let ctor = DummyCtor {
+            connect_outcomes,
+            poll_read_results,
+            ..DummyCtor::default()
+        };
+
+        let disconnects: Arc<Mutex<Vec<ReconnectContext>>> = Arc::default();
+        let fails: Arc<Mutex<Vec<ReconnectContext>>> = Arc::default();
+        let connects: Arc<Mutex<Vec<ReconnectContext>>> = Arc::default();
+        let (disconnects_clone, fails_clone, connects_clone) =
+            (disconnects.clone(), fails.clone(), connects.clone());
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(20), Duration::from_millis(30)])
+            .with_on_disconnect_callback_ctx(move |ctx| {
+                disconnects_clone.lock().unwrap().push(ctx.clone())
+            })
+            .with_on_connect_fail_callback_ctx(move |ctx| {
+                fails_clone.lock().unwrap().push(ctx.clone())
+            })
+            .with_on_connect_callback_ctx(move |ctx| {
+                connects_clone.lock().unwrap().push(ctx.clone())
+            });
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert_eq!(dummy.next().await.unwrap(), b"e\n".to_vec());
+
+        let disconnects = disconnects.lock().unwrap();
+        assert_eq!(disconnects.len(), 1);
+        assert_eq!(disconnects[0].attempt, 0);
+        assert_eq!(disconnects[0].next_delay, Some(Duration::from_millis(20)));
+
+        let fails = fails.lock().unwrap();
+        assert_eq!(fails.len(), 1);
+        assert_eq!(fails[0].attempt, 1);
+        assert_eq!(fails[0].next_delay, Some(Duration::from_millis(30)));
+        assert!(fails[0].downtime >= Duration::from_millis(20));
+
+        let connects = connects.lock().unwrap();
+        assert_eq!(connects.len(), 2);
+        assert_eq!(connects[1].attempt, 2);
+        assert!(connects[1].downtime >= Duration::from_millis(50));
+    }
+}
+
+#[cfg(test)]
+mod limits {
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    fn fatal_then(connect_outcomes: Vec<bool>) -> DummyCtor {
+        DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(connect_outcomes)),
+            poll_read_results: Arc::new(Mutex::new(vec![(
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "fatal",
+                ))),
+                vec![],
+            )])),
+            ..DummyCtor::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn should_give_up_when_reconnect_duration_exceeded() {
+        let ctor = fatal_then(vec![true, false, false, false, false, false]);
+
+        let fail_counter = Arc::new(AtomicU8::new(0));
+        let fail_clone = fail_counter.clone();
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| std::iter::repeat(Duration::from_millis(50)))
+            .with_max_reconnect_duration(Duration::from_millis(175))
+            .with_on_connect_fail_callback(move || {
+                fail_clone.fetch_add(1, Ordering::Relaxed);
+            });
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+
+        assert!(dummy.next().await.is_none());
+        assert_eq!(fail_counter.load(Ordering::Relaxed), 3);
+    }
+
+    #[tokio::test]
+    async fn should_give_up_when_max_attempts_reached() {
+        let ctor = fatal_then(vec![true, false, false, false, false]);
+
+        let fail_counter = Arc::new(AtomicU8::new(0));
+        let fail_clone = fail_counter.clone();
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| std::iter::repeat(Duration::from_millis(10)))
+            .with_max_attempts(2)
+            .with_on_connect_fail_callback(move || {
+                fail_clone.fetch_add(1, Ordering::Relaxed);
+            });
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+
+        assert!(dummy.next().await.is_none());
+        assert_eq!(fail_counter.load(Ordering::Relaxed), 2);
+    }
+}
+
+#[cfg(test)]
+mod forced {
+    use futures::stream::StreamExt;
+
+    use stream_reconnect::ConnectionStatus;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_reconnect_when_forced() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![true, true]));
+
+        let poll_read_results = Arc::new(Mutex::new(vec![
+            (Poll::Ready(Ok(())), b"a".to_vec()),
+            (Poll::Ready(Ok(())), b"b".to_vec()),
+        ]));
+
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            poll_read_results,
+            ..DummyCtor::default()
+        };
+
+        let disconnect_counter = Arc::new(AtomicU8::new(0));
+        let disconnect_clone = disconnect_counter.clone();
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(10)])
+            .with_on_disconnect_callback(move || {
+                disconnect_clone.fetch_add(1, Ordering::Relaxed);
+            });
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+
+        dummy.force_reconnect();
+        assert_eq!(
+            dummy.status(),
+            ConnectionStatus::Reconnecting { attempt: 1 }
+        );
+        assert_eq!(disconnect_counter.load(Ordering::Relaxed), 1);
+
+        assert_eq!(dummy.next().await.unwrap(), b"b".to_vec());
+        assert_eq!(dummy.status(), ConnectionStatus::Connected);
+        assert!(connect_outcomes.lock().unwrap().is_empty());
+    }
+}
+
+#[cfg(test)]
+mod outbound_buffer {
+    use futures::stream::StreamExt;
+    use futures::SinkExt;
+
+    use stream_reconnect::{ConnectionStatus, OutboundOverflowPolicy, ReconnectError};
+
+    use super::*;
+
+    fn disconnecting_ctor() -> DummyCtor {
+        DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, true])),
+            poll_read_results: Arc::new(Mutex::new(vec![(
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "fatal",
+                ))),
+                vec![],
+            )])),
+            ..DummyCtor::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn should_buffer_items_while_disconnected_and_send_after_reconnect() {
+        let ctor = disconnecting_ctor();
+        let sent_items = ctor.sent_items.clone();
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(50)])
+            .with_outbound_buffer(2);
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert!(futures::poll!(dummy.next()).is_pending());
+        assert_eq!(
+            dummy.status(),
+            ConnectionStatus::Reconnecting { attempt: 1 }
+        );
+
+        dummy.feed(b"a".to_vec()).await.unwrap();
+        dummy.feed(b"b".to_vec()).await.unwrap();
+        assert!(matches!(
+            dummy.feed(b"c".to_vec()).await,
+            Err(ReconnectError::OutboundBufferFull)
+        ));
+        assert_eq!(dummy.stats().dropped_outbound, 1);
+        assert!(sent_items.lock().unwrap().is_empty());
+
+        dummy.flush().await.unwrap();
+        assert_eq!(dummy.status(), ConnectionStatus::Connected);
+        assert_eq!(
+            *sent_items.lock().unwrap(),
+            vec![b"a".to_vec(), b"b".to_vec()]
+        );
+    }
+
+    #[tokio::test]
+    async fn should_drop_the_oldest_item_when_full() {
+        let ctor = disconnecting_ctor();
+        let sent_items = ctor.sent_items.clone();
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(50)])
+            .with_outbound_buffer(2)
+            .with_outbound_overflow_policy(OutboundOverflowPolicy::DropOldest);
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert!(futures::poll!(dummy.next()).is_pending());
+
+        dummy.feed(b"a".to_vec()).await.unwrap();
+        dummy.feed(b"b".to_vec()).await.unwrap();
+        dummy.feed(b"c".to_vec()).await.unwrap();
+        assert_eq!(dummy.stats().dropped_outbound, 1);
+
+        dummy.flush().await.unwrap();
+        assert_eq!(
+            *sent_items.lock().unwrap(),
+            vec![b"b".to_vec(), b"c".to_vec()]
+        );
+    }
+
+    #[tokio::test]
+    async fn should_block_until_reconnected_when_full() {
+        let ctor = disconnecting_ctor();
+        let sent_items = ctor.sent_items.clone();
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(50)])
+            .with_outbound_buffer(2)
+            .with_outbound_overflow_policy(OutboundOverflowPolicy::Block);
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert!(futures::poll!(dummy.next()).is_pending());
+
+        dummy.feed(b"a".to_vec()).await.unwrap();
+        dummy.feed(b"b".to_vec()).await.unwrap();
+        dummy.feed(b"c".to_vec()).await.unwrap();
+        assert_eq!(dummy.status(), ConnectionStatus::Connected);
+        assert_eq!(dummy.stats().dropped_outbound, 0);
+
+        dummy.flush().await.unwrap();
+        assert_eq!(
+            *sent_items.lock().unwrap(),
+            vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()]
+        );
+    }
+
+    #[tokio::test]
+    async fn should_flush_each_buffered_item_before_sending_the_next() {
+        let ctor = DummyCtor {
+            slow_flush: true,
+            ..disconnecting_ctor()
+        };
+        let sent_items = ctor.sent_items.clone();
+        let unflushed_at_send = ctor.unflushed_at_send.clone();
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(20)])
+            .with_outbound_buffer(3);
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert!(futures::poll!(dummy.next()).is_pending());
+        for item in [b"a", b"b", b"c"] {
+            dummy.feed(item.to_vec()).await.unwrap();
+        }
+
+        dummy.wait_connected().await.unwrap();
+        dummy.send(b"d".to_vec()).await.unwrap();
+        assert_eq!(
+            *sent_items.lock().unwrap(),
+            vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec(), b"d".to_vec()]
+        );
+        assert_eq!(*unflushed_at_send.lock().unwrap(), vec![0, 0, 0, 0]);
+    }
+
+    #[tokio::test]
+    async fn should_flush_buffered_items_by_batches() {
+        let ctor = disconnecting_ctor();
+        let sent_items = ctor.sent_items.clone();
+        let unflushed_at_send = ctor.unflushed_at_send.clone();
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(20)])
+            .with_outbound_buffer(5)
+            .with_flush_batch_size(2);
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert!(futures::poll!(dummy.next()).is_pending());
+        for item in [b"a", b"b", b"c"] {
+            dummy.feed(item.to_vec()).await.unwrap();
+        }
+
+        dummy.wait_connected().await.unwrap();
+        dummy.send(b"d".to_vec()).await.unwrap();
+        assert_eq!(sent_items.lock().unwrap().len(), 4);
+        assert_eq!(*unflushed_at_send.lock().unwrap(), vec![0, 1, 0, 0]);
+    }
+
+    #[tokio::test]
+    async fn should_take_pending_items_once_dead() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, false])),
+            ..disconnecting_ctor()
+        };
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(50)])
+            .with_outbound_buffer(2);
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert!(futures::poll!(dummy.next()).is_pending());
+        dummy.feed(b"a".to_vec()).await.unwrap();
+        dummy.feed(b"b".to_vec()).await.unwrap();
+        assert!(dummy.take_pending::<Vec<u8>>().is_empty());
+
+        assert!(dummy.next().await.is_none());
+        assert!(dummy.is_dead());
+        assert_eq!(
+            dummy.take_pending::<Vec<u8>>(),
+            vec![b"a".to_vec(), b"b".to_vec()]
+        );
+        assert!(dummy.take_pending::<Vec<u8>>().is_empty());
+    }
+
+    /// A stream which also accepts text items, as sinks of several item types do.
+    struct MultiSink(DummyStream);
+
+    impl Stream for MultiSink {
+        type Item = Vec<u8>;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Vec<u8>>> {
+            Pin::new(&mut self.0).poll_next(cx)
+        }
+    }
+
+    impl Sink<Vec<u8>> for MultiSink {
+        type Error = io::Error;
+
+        fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.0).poll_ready(cx)
+        }
+
+        fn start_send(mut self: Pin<&mut Self>, item: Vec<u8>) -> io::Result<()> {
+            Pin::new(&mut self.0).start_send(item)
+        }
+
+        fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.0).poll_flush(cx)
+        }
+
+        fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.0).poll_close(cx)
+        }
+    }
+
+    impl Sink<&'static str> for MultiSink {
+        type Error = io::Error;
+
+        fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Sink::<Vec<u8>>::poll_ready(self, cx)
+        }
+
+        fn start_send(self: Pin<&mut Self>, item: &'static str) -> io::Result<()> {
+            self.start_send(item.as_bytes().to_vec())
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Sink::<Vec<u8>>::poll_flush(self, cx)
+        }
+
+        fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Sink::<Vec<u8>>::poll_close(self, cx)
+        }
+    }
+
+    struct MultiSinkConnector;
+
+    impl UnderlyingStream<DummyCtor, Vec<u8>, io::Error> for MultiSinkConnector {
+        type Stream = MultiSink;
+
+        #[cfg(not(feature = "not-send"))]
+        async fn establish(ctor: DummyCtor) -> io::Result<MultiSink> {
+            DummyStreamConnector::establish(ctor).await.map(MultiSink)
+        }
+
+        fn is_write_disconnect_error(_err: &Error) -> bool {
+            true
+        }
+    }
+
+    type ReconnectMultiSink = ReconnectStream<MultiSinkConnector, DummyCtor, Vec<u8>, io::Error>;
+
+    #[tokio::test]
+    async fn should_reject_items_of_another_type_than_the_buffered_ones() {
+        let ctor = disconnecting_ctor();
+        let sent_items = ctor.sent_items.clone();
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(50)])
+            .with_outbound_buffer(2);
+
+        let mut stream = ReconnectMultiSink::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert!(futures::poll!(stream.next()).is_pending());
+        stream.feed(b"a".to_vec()).await.unwrap();
+        assert!(matches!(
+            stream.feed("b").await,
+            Err(ReconnectError::ItemTypeMismatch)
+        ));
+
+        stream.wait_connected().await.unwrap();
+        assert!(matches!(
+            SinkExt::<&str>::flush(&mut stream).await,
+            Err(ReconnectError::ItemTypeMismatch)
+        ));
+        SinkExt::<Vec<u8>>::flush(&mut stream).await.unwrap();
+        assert_eq!(*sent_items.lock().unwrap(), vec![b"a".to_vec()]);
+    }
+
+    #[tokio::test]
+    async fn should_only_take_pending_items_of_the_given_type() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, false])),
+            ..disconnecting_ctor()
+        };
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(50)])
+            .with_outbound_buffer(2);
+
+        let mut stream = ReconnectMultiSink::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert!(futures::poll!(stream.next()).is_pending());
+        stream.feed(b"a".to_vec()).await.unwrap();
+
+        assert!(stream.next().await.is_none());
+        assert!(stream.take_pending::<&str>().is_empty());
+        assert_eq!(stream.take_pending::<Vec<u8>>(), vec![b"a".to_vec()]);
+    }
+
+    #[tokio::test]
+    async fn should_not_resend_items_of_another_type_than_the_resent_ones() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, true])),
+            flush_results: Arc::new(Mutex::new(vec![Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "fatal",
+            ))])),
+            ..DummyCtor::default()
+        };
+        let sent_items = ctor.sent_items.clone();
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(10)])
+            .with_resend_unflushed::<Vec<u8>>(true);
+
+        let mut stream = ReconnectMultiSink::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        stream.send("a").await.unwrap();
+        stream.send(b"b".to_vec()).await.unwrap();
+
+        assert_eq!(
+            *sent_items.lock().unwrap(),
+            vec![b"a".to_vec(), b"b".to_vec()]
+        );
+    }
+
+    #[tokio::test]
+    async fn should_only_pass_items_of_the_given_type_to_the_sent_hook() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true])),
+            ..DummyCtor::default()
+        };
+        let sent: Arc<Mutex<Vec<&str>>> = Arc::default();
+        let sent_clone = sent.clone();
+        let options = ReconnectOptions::new()
+            .with_on_item_sent(move |item: &&str| sent_clone.lock().unwrap().push(*item));
+
+        let mut stream = ReconnectMultiSink::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        stream.send(b"a".to_vec()).await.unwrap();
+        stream.send("b").await.unwrap();
+
+        assert_eq!(*sent.lock().unwrap(), vec!["b"]);
+    }
+}
+
+#[cfg(test)]
+mod errors {
+    use futures::stream::StreamExt;
+    use futures::SinkExt;
+
+    use stream_reconnect::ReconnectError;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_report_initial_connect_failure() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![false])),
+            ..DummyCtor::default()
+        };
+
+        let result = ReconnectDummy::connect(ctor).await;
+
+        assert!(matches!(
+            result,
+            Err(ReconnectError::InitialConnectFailed(e)) if e.kind() == ErrorKind::NotConnected
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_name_errors_of_named_streams() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![false])),
+            ..DummyCtor::default()
+        };
+
+        let options = ReconnectOptions::new().with_name("feed");
+        let err = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.name(), Some("feed"));
+        assert!(matches!(
+            err.unnamed(),
+            ReconnectError::InitialConnectFailed(_)
+        ));
+        assert_eq!(
+            err.to_string(),
+            "feed: initial connection failed: So unfortunate"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_report_exhaustion_on_send() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, false])),
+            poll_read_results: Arc::new(Mutex::new(vec![(
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "fatal",
+                ))),
+                vec![],
+            )])),
+            ..DummyCtor::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(10)]);
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert!(dummy.next().await.is_none());
+
+        assert!(matches!(
+            dummy.send(b"a".to_vec()).await,
+            Err(ReconnectError::ReconnectsExhausted)
+        ));
+    }
+
+    /// A connector written against the trait as it was when it required `exhaust_err`.
+    struct LegacyConnector;
+
+    impl UnderlyingStream<DummyCtor, Vec<u8>, io::Error> for LegacyConnector {
+        type Stream = DummyStream;
+
+        #[cfg(not(feature = "not-send"))]
+        async fn establish(ctor: DummyCtor) -> io::Result<DummyStream> {
+            DummyStreamConnector::establish(ctor).await
+        }
+
+        fn is_write_disconnect_error(_err: &Error) -> bool {
+            true
+        }
+
+        fn exhaust_err() -> io::Error {
+            io::Error::other("exhausted")
+        }
+    }
+
+    #[tokio::test]
+    async fn should_report_exhaustion_with_a_legacy_exhaust_err() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true])),
+            poll_read_results: Arc::new(Mutex::new(vec![(
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "fatal",
+                ))),
+                vec![],
+            )])),
+            ..DummyCtor::default()
+        };
+
+        let options = ReconnectOptions::new().with_retries_generator(Vec::new);
+        let mut dummy =
+            ReconnectStream::<LegacyConnector, _, Vec<u8>, io::Error>::connect_with_options(
+                ctor, options,
+            )
+            .await
+            .unwrap();
+        assert!(dummy.next().await.is_none());
+
+        assert!(matches!(
+            dummy.send(b"a".to_vec()).await,
+            Err(ReconnectError::ReconnectsExhausted)
+        ));
+    }
+}
+
+#[cfg(test)]
+mod stats {
+    use futures::stream::StreamExt;
+    use futures::SinkExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_count_disconnects_and_attempts() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![false, true, false, true])),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (
+                    Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "fatal",
+                    ))),
+                    vec![],
+                ),
+                (Poll::Ready(Ok(())), b"e\n".to_vec()),
+            ])),
+            ..DummyCtor::default()
+        };
+
+        let options = ReconnectOptions::new()
+            .with_exit_if_first_connect_fails(false)
+            .with_retries_generator(|| vec![Duration::from_millis(20), Duration::from_millis(20)]);
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert_eq!(dummy.next().await.unwrap(), b"e\n".to_vec());
+
+        let stats = dummy.stats();
+        assert_eq!(stats.disconnects, 1);
+        assert_eq!(stats.reconnects, 1);
+        assert_eq!(stats.failed_attempts, 2);
+        assert!(stats.downtime >= Duration::from_millis(40));
+    }
+
+    #[tokio::test]
+    async fn should_track_uptime_and_current_downtime() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, true])),
+            poll_read_results: Arc::new(Mutex::new(vec![(Poll::Ready(Ok(())), b"a".to_vec())])),
+            ..DummyCtor::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(20)]);
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(dummy.stats().total_uptime() >= Duration::from_millis(30));
+        assert_eq!(dummy.stats().current_downtime(), Duration::ZERO);
+
+        dummy.force_reconnect();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let stats = dummy.stats();
+        assert!(stats.current_downtime() >= Duration::from_millis(5));
+        assert_eq!(stats.downtime, stats.current_downtime());
+
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+        let stats = dummy.stats();
+        assert_eq!(stats.current_downtime(), Duration::ZERO);
+        assert!(stats.downtime >= Duration::from_millis(20));
+        assert!(stats.total_uptime() >= Duration::from_millis(30));
+    }
+
+    #[tokio::test]
+    async fn should_record_the_last_transitions() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, true])),
+            poll_read_results: Arc::new(Mutex::new(vec![(Poll::Ready(Ok(())), b"a".to_vec())])),
+            ..DummyCtor::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(10)]);
+        let before_connect = std::time::Instant::now();
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        let first_connect = dummy.last_connected_at().unwrap();
+        assert!(first_connect >= before_connect);
+        assert!(dummy.last_disconnected_at().is_none());
+
+        dummy.force_reconnect();
+        let disconnect = dummy.last_disconnected_at().unwrap();
+        assert!(disconnect >= first_connect);
+        assert_eq!(dummy.last_connected_at(), Some(first_connect));
+
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+        let reconnect = dummy.last_connected_at().unwrap();
+        assert!(reconnect >= disconnect + Duration::from_millis(10));
+        assert_eq!(dummy.last_disconnected_at(), Some(disconnect));
+    }
+
+    #[tokio::test]
+    async fn should_reset_connection_counters_on_reconnect() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, true])),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+                (Poll::Ready(Ok(())), b"b".to_vec()),
+                (
+                    Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "fatal",
+                    ))),
+                    vec![],
+                ),
+                (Poll::Ready(Ok(())), b"c".to_vec()),
+            ])),
+            ..DummyCtor::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(10)]);
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        dummy.send(b"x".to_vec()).await.unwrap();
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+        assert_eq!(dummy.next().await.unwrap(), b"b".to_vec());
+        let connection = dummy.stats().connection;
+        assert_eq!(connection.items_sent_since_connect, 1);
+        assert_eq!(connection.items_received_since_connect, 2);
+
+        assert_eq!(dummy.next().await.unwrap(), b"c".to_vec());
+        let connection = dummy.stats().connection;
+        assert_eq!(connection.items_sent_since_connect, 0);
+        assert_eq!(connection.items_received_since_connect, 1);
+    }
+
+    #[tokio::test]
+    async fn should_reset_counters_mid_interval() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, true])),
+            poll_read_results: Arc::new(Mutex::new(vec![(Poll::Ready(Ok(())), b"a".to_vec())])),
+            ..DummyCtor::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(40)]);
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        let disconnected_at = std::time::Instant::now();
+        dummy.force_reconnect();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+
+        dummy.reset_stats();
+        let stats = dummy.stats();
+        assert_eq!(stats.disconnects, 0);
+        assert!(stats.downtime < Duration::from_millis(10));
+        assert!(stats.current_downtime() >= Duration::from_millis(30));
+
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+        let stats = dummy.stats();
+        assert_eq!(stats.reconnects, 1);
+        assert!(stats.downtime + Duration::from_millis(30) <= disconnected_at.elapsed());
+        assert!(stats.total_uptime() < Duration::from_millis(10));
+    }
+}
+
+#[cfg(test)]
+mod ctor_arg_refresh {
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_reconnect_with_refreshed_ctor_arg() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true])),
+            poll_read_results: Arc::new(Mutex::new(vec![(
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "fatal",
+                ))),
+                vec![],
+            )])),
+            ..DummyCtor::default()
+        };
+        let refreshed = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true])),
+            poll_read_results: Arc::new(Mutex::new(vec![(Poll::Ready(Ok(())), b"fresh".to_vec())])),
+            ..DummyCtor::default()
+        };
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(10)])
+            .with_ctor_arg_refresh(move || {
+                let refreshed = refreshed.clone();
+                async move { refreshed }
+            });
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+
+        assert_eq!(dummy.next().await.unwrap(), b"fresh".to_vec());
+    }
+
+    #[tokio::test]
+    async fn should_keep_the_ctor_arg_if_the_refresh_returns_another_type() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, true])),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (
+                    Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "fatal",
+                    ))),
+                    vec![],
+                ),
+                (Poll::Ready(Ok(())), b"same".to_vec()),
+            ])),
+            ..DummyCtor::default()
+        };
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(10)])
+            .with_ctor_arg_refresh(|| async { String::from("not a ctor arg") });
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+
+        assert_eq!(dummy.next().await.unwrap(), b"same".to_vec());
+    }
+}
+
+#[cfg(test)]
+mod idle_timeout {
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_reconnect_when_nothing_is_read_within_timeout() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, true])),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (Poll::Pending, vec![]),
+                (Poll::Pending, vec![]),
+                (Poll::Ready(Ok(())), b"e\n".to_vec()),
+            ])),
+            ..DummyCtor::default()
+        };
+
+        let disconnect_counter = Arc::new(AtomicU8::new(0));
+        let disconnect_clone = disconnect_counter.clone();
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(10)])
+            .with_idle_timeout(Duration::from_millis(50))
+            .with_on_disconnect_callback(move || {
+                disconnect_clone.fetch_add(1, Ordering::Relaxed);
+            });
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+
+        let msg = tokio::time::timeout(Duration::from_secs(1), dummy.next())
+            .await
+            .unwrap();
+        assert_eq!(msg.unwrap(), b"e\n".to_vec());
+        assert_eq!(disconnect_counter.load(Ordering::Relaxed), 1);
+    }
+}
+
+#[cfg(test)]
+mod resend_unflushed {
+    use futures::SinkExt;
+
+    use super::*;
+
+    fn ctor_failing_first_flush() -> DummyCtor {
+        DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, true])),
+            flush_results: Arc::new(Mutex::new(vec![Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "fatal",
+            ))])),
+            ..DummyCtor::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn should_resend_unflushed_items_after_reconnect() {
+        let ctor = ctor_failing_first_flush();
+        let sent_items = ctor.sent_items.clone();
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(10)])
+            .with_resend_unflushed::<Vec<u8>>(true);
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        dummy.send(b"a".to_vec()).await.unwrap();
+        dummy.send(b"b".to_vec()).await.unwrap();
+
+        assert_eq!(
+            *sent_items.lock().unwrap(),
+            vec![b"a".to_vec(), b"a".to_vec(), b"b".to_vec()]
+        );
+    }
+
+    #[tokio::test]
+    async fn should_drop_unflushed_items_by_default() {
+        let ctor = ctor_failing_first_flush();
+        let sent_items = ctor.sent_items.clone();
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(10)]);
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        dummy.send(b"a".to_vec()).await.unwrap();
+        dummy.send(b"b".to_vec()).await.unwrap();
+
+        assert_eq!(
+            *sent_items.lock().unwrap(),
+            vec![b"a".to_vec(), b"b".to_vec()]
+        );
+    }
+}
+
+#[cfg(test)]
+mod pause {
+    use futures::stream::StreamExt;
+
+    use stream_reconnect::ConnectionStatus;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_not_reconnect_until_resumed() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![true, true]));
+
+        let poll_read_results = Arc::new(Mutex::new(vec![
+            (Poll::Ready(Ok(())), b"a".to_vec()),
+            (Poll::Ready(Ok(())), b"b".to_vec()),
+        ]));
+
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            poll_read_results,
+            ..DummyCtor::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(10)]);
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+
+        dummy.pause();
+        dummy.force_reconnect();
+        assert_eq!(dummy.status(), ConnectionStatus::Disconnected);
+        assert_eq!(dummy.next_retry_in(), None);
+
+        let paused = tokio::time::timeout(Duration::from_millis(50), dummy.next()).await;
+        assert!(paused.is_err());
+        assert_eq!(connect_outcomes.lock().unwrap().len(), 1);
+
+        dummy.resume();
+        assert_eq!(
+            dummy.status(),
+            ConnectionStatus::Reconnecting { attempt: 1 }
+        );
+        assert_eq!(dummy.next().await.unwrap(), b"b".to_vec());
+        assert_eq!(dummy.status(), ConnectionStatus::Connected);
+        assert!(connect_outcomes.lock().unwrap().is_empty());
+    }
+}
+
+#[cfg(test)]
+mod reset_after_stable {
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    /// Disconnects twice in a row and returns the delays scheduled on each disconnect,
+    /// along with the number of failed attempts.
+    async fn disconnect_delays(options: ReconnectOptions) -> (Vec<Option<Duration>>, u64) {
+        let connect_outcomes = Arc::new(Mutex::new(vec![true, true, true]));
+
+        let fatal = || {
+            (
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "fatal",
+                ))),
+                vec![],
+            )
+        };
+        let poll_read_results = Arc::new(Mutex::new(vec![
+            fatal(),
+            fatal(),
+            (Poll::Ready(Ok(())), b"e\n".to_vec()),
+        ]));
+
+        let ctor = DummyCtor {
+            connect_outcomes,
+            poll_read_results,
+            ..DummyCtor::default()
+        };
+
+        let delays: Arc<Mutex<Vec<Option<Duration>>>> = Arc::default();
+        let delays_clone = delays.clone();
+
+        let options = options
+            .with_retries_generator(|| {
+                vec![
+                    Duration::from_millis(10),
+                    Duration::from_millis(20),
+                    Duration::from_millis(30),
+                ]
+            })
+            .with_on_disconnect_callback_ctx(move |ctx| {
+                delays_clone.lock().unwrap().push(ctx.next_delay)
+            });
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert_eq!(dummy.next().await.unwrap(), b"e\n".to_vec());
+
+        let delays = delays.lock().unwrap().clone();
+        (delays, dummy.stats().failed_attempts)
+    }
+
+    #[tokio::test]
+    async fn should_continue_backoff_when_connection_is_not_stable() {
+        let (delays, failed_attempts) = disconnect_delays(
+            ReconnectOptions::new().with_reset_after_stable(Duration::from_secs(5)),
+        )
+        .await;
+        assert_eq!(
+            delays,
+            vec![
+                Some(Duration::from_millis(10)),
+                Some(Duration::from_millis(20))
+            ]
+        );
+        assert_eq!(failed_attempts, 0);
+    }
+
+    #[tokio::test]
+    async fn should_restart_backoff_by_default() {
+        let (delays, _) = disconnect_delays(ReconnectOptions::new()).await;
+        assert_eq!(
+            delays,
+            vec![
+                Some(Duration::from_millis(10)),
+                Some(Duration::from_millis(10))
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn should_count_unhealthy_connections_as_failed_attempts() {
+        let (delays, failed_attempts) = disconnect_delays(
+            ReconnectOptions::new().with_min_healthy_duration(Duration::from_secs(5)),
+        )
+        .await;
+        assert_eq!(
+            delays,
+            vec![
+                Some(Duration::from_millis(10)),
+                Some(Duration::from_millis(20))
+            ]
+        );
+        // both connections dropped right away, the initial one included
+        assert_eq!(failed_attempts, 2);
+    }
+}
+
+#[cfg(test)]
+mod retries_from_error {
+    use futures::SinkExt;
+
+    use super::*;
+
+    fn recording_options(kinds: Arc<Mutex<Vec<ErrorKind>>>) -> ReconnectOptions {
+        ReconnectOptions::new()
+            // would give up right away if the error was not passed to the generator
+            .with_retries_generator(Vec::new)
+            .with_retries_generator_from_error(move |err: &io::Error| {
+                kinds.lock().unwrap().push(err.kind());
+                vec![Duration::from_millis(10)]
+            })
+    }
+
+    #[tokio::test]
+    async fn should_pass_initial_connect_error_to_generator() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![false, true]));
+        let ctor = DummyCtor {
+            connect_outcomes,
+            ..DummyCtor::default()
+        };
+
+        let kinds: Arc<Mutex<Vec<ErrorKind>>> = Arc::default();
+        let options = recording_options(kinds.clone()).with_exit_if_first_connect_fails(false);
+
+        let dummy = ReconnectDummy::connect_with_options(ctor, options).await;
+        assert!(dummy.is_ok());
+        assert_eq!(*kinds.lock().unwrap(), vec![ErrorKind::NotConnected]);
+    }
+
+    #[tokio::test]
+    async fn should_pass_write_error_to_generator() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![true, true]));
+        let flush_results = Arc::new(Mutex::new(vec![Err(io::Error::new(
+            ErrorKind::ConnectionReset,
+            "reset",
+        ))]));
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            flush_results,
+            ..DummyCtor::default()
+        };
+
+        let kinds: Arc<Mutex<Vec<ErrorKind>>> = Arc::default();
+        let mut dummy =
+            ReconnectDummy::connect_with_options(ctor, recording_options(kinds.clone()))
+                .await
+                .unwrap();
+
+        dummy.send(b"a".to_vec()).await.unwrap();
+        assert_eq!(*kinds.lock().unwrap(), vec![ErrorKind::ConnectionReset]);
+        assert!(connect_outcomes.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_use_the_default_retries_if_the_error_type_does_not_match() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![false, true]));
+        let ctor = DummyCtor {
+            connect_outcomes,
+            ..DummyCtor::default()
+        };
+
+        let options = ReconnectOptions::new()
+            .with_exit_if_first_connect_fails(false)
+            .with_retries_generator(|| vec![Duration::from_millis(10)])
+            .with_retries_generator_from_error(|_: &String| Vec::new());
+
+        let dummy = ReconnectDummy::connect_with_options(ctor, options).await;
+        assert!(dummy.is_ok());
+    }
+}
+
+#[cfg(test)]
+mod debug {
+    use super::*;
+
+    #[tokio::test]
+    async fn should_show_status_in_debug_output() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true])),
+            ..DummyCtor::default()
+        };
+        let dummy = ReconnectDummy::connect(ctor).await.unwrap();
+        assert_eq!(
+            format!("{:?}", dummy),
+            "ReconnectStream { status: Connected, attempt: 0, .. }"
+        );
+    }
+}
+
+#[cfg(test)]
+mod set_options {
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    fn recording_options(
+        delays: Arc<Mutex<Vec<Option<Duration>>>>,
+        retries: Vec<Duration>,
+    ) -> ReconnectOptions {
+        let fail_delays = delays.clone();
+        ReconnectOptions::new()
+            .with_retries_generator(move || retries.clone())
+            .with_on_disconnect_callback_ctx(move |ctx| delays.lock().unwrap().push(ctx.next_delay))
+            .with_on_connect_fail_callback_ctx(move |ctx| {
+                fail_delays.lock().unwrap().push(ctx.next_delay)
+            })
+    }
+
+    #[tokio::test]
+    async fn should_use_new_options_on_next_disconnect() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, true])),
+            poll_read_results: Arc::new(Mutex::new(vec![(Poll::Ready(Ok(())), b"a".to_vec())])),
+            ..DummyCtor::default()
+        };
+
+        let old_delays: Arc<Mutex<Vec<Option<Duration>>>> = Arc::default();
+        let new_delays: Arc<Mutex<Vec<Option<Duration>>>> = Arc::default();
+
+        let mut dummy = ReconnectDummy::connect_with_options(
+            ctor,
+            recording_options(old_delays.clone(), vec![Duration::from_millis(30)]),
+        )
+        .await
+        .unwrap();
+        dummy.set_options(recording_options(
+            new_delays.clone(),
+            vec![Duration::from_millis(5)],
+        ));
+        dummy.force_reconnect();
+
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+        assert!(old_delays.lock().unwrap().is_empty());
+        assert_eq!(
+            *new_delays.lock().unwrap(),
+            vec![Some(Duration::from_millis(5))]
+        );
+    }
+
+    #[tokio::test]
+    async fn should_replace_retries_at_next_attempt_while_reconnecting() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, false, true])),
+            poll_read_results: Arc::new(Mutex::new(vec![(Poll::Ready(Ok(())), b"a".to_vec())])),
+            ..DummyCtor::default()
+        };
+
+        let old_delays: Arc<Mutex<Vec<Option<Duration>>>> = Arc::default();
+        let new_delays: Arc<Mutex<Vec<Option<Duration>>>> = Arc::default();
+
+        let mut dummy = ReconnectDummy::connect_with_options(
+            ctor,
+            recording_options(
+                old_delays.clone(),
+                vec![Duration::from_millis(10), Duration::from_millis(30)],
+            ),
+        )
+        .await
+        .unwrap();
+        dummy.force_reconnect();
+        dummy.set_options(recording_options(
+            new_delays.clone(),
+            vec![Duration::from_millis(5)],
+        ));
+
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+        assert_eq!(
+            *old_delays.lock().unwrap(),
+            vec![Some(Duration::from_millis(10))]
+        );
+        assert_eq!(
+            *new_delays.lock().unwrap(),
+            vec![Some(Duration::from_millis(5))]
+        );
+    }
+}
+
+#[cfg(test)]
+mod shutdown {
+    use futures::stream::StreamExt;
+    use futures::SinkExt;
+    use tokio::sync::oneshot;
+
+    use stream_reconnect::{ConnectionStatus, ReconnectError};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_stop_reconnecting_when_signalled_mid_backoff() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, true])),
+            ..DummyCtor::default()
+        };
+
+        let (tx, rx) = oneshot::channel::<()>();
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_secs(60)])
+            .with_shutdown_signal(async {
+                let _ = rx.await;
+            });
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        dummy.force_reconnect();
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            let _ = tx.send(());
+        });
+        let next = tokio::time::timeout(Duration::from_secs(5), dummy.next()).await;
+        assert_eq!(next.unwrap(), None);
+        assert_eq!(dummy.status(), ConnectionStatus::Shutdown);
+        assert!(!dummy.is_dead());
+
+        let sent = dummy.send(b"a".to_vec()).await;
+        assert!(matches!(sent, Err(ReconnectError::Shutdown)));
+    }
+
+    #[tokio::test]
+    async fn should_give_up_initial_connect_when_signalled() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![false])),
+            ..DummyCtor::default()
+        };
+
+        let options = ReconnectOptions::new()
+            .with_exit_if_first_connect_fails(false)
+            .with_retries_generator(|| vec![Duration::from_secs(60)])
+            .with_shutdown_signal(async {});
+
+        let dummy = ReconnectDummy::connect_with_options(ctor, options).await;
+        assert!(matches!(dummy, Err(ReconnectError::Shutdown)));
+    }
+}
+
+#[cfg(test)]
+mod connect_timeout {
+    use futures::stream::StreamExt;
+
+    use stream_reconnect::ReconnectError;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_retry_when_connect_attempt_times_out() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![true, true, true]));
+        let connect_delays = Arc::new(Mutex::new(vec![Duration::ZERO, Duration::from_secs(60)]));
+
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            connect_delays,
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (Poll::Ready(Err(io::Error::other("eof"))), vec![]),
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+            ])),
+            ..DummyCtor::default()
+        };
+
+        let fail_errors: Arc<Mutex<Vec<String>>> = Arc::default();
+        let fail_errors_clone = fail_errors.clone();
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(10), Duration::from_millis(10)])
+            .with_connect_timeout(Duration::from_millis(50))
+            .with_on_connect_fail_callback_err(move |err| {
+                fail_errors_clone.lock().unwrap().push(err.to_string())
+            });
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+
+        let next = tokio::time::timeout(Duration::from_secs(5), dummy.next()).await;
+        assert_eq!(next.unwrap().unwrap(), b"a".to_vec());
+        assert_eq!(
+            *fail_errors.lock().unwrap(),
+            vec![ReconnectError::<io::Error>::ConnectTimeout.to_string()]
+        );
+        assert_eq!(dummy.stats().failed_attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn should_report_timeout_of_initial_connect() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true])),
+            connect_delays: Arc::new(Mutex::new(vec![Duration::from_secs(60)])),
+            ..DummyCtor::default()
+        };
+
+        let options = ReconnectOptions::new().with_connect_timeout(Duration::from_millis(10));
+
+        let dummy = ReconnectDummy::connect_with_options(ctor, options).await;
+        assert!(matches!(dummy, Err(ReconnectError::ConnectTimeout)));
+    }
+
+    #[tokio::test]
+    async fn should_compute_the_timeout_of_each_attempt() {
+        let connect_delays = Arc::new(Mutex::new(vec![
+            Duration::ZERO,
+            Duration::from_millis(100),
+            Duration::from_millis(100),
+        ]));
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, true, true])),
+            connect_delays,
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (Poll::Ready(Err(io::Error::other("eof"))), vec![]),
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+            ])),
+            ..DummyCtor::default()
+        };
+
+        let timed_attempts: Arc<Mutex<Vec<usize>>> = Arc::default();
+        let timed_attempts_clone = timed_attempts.clone();
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(10), Duration::from_millis(10)])
+            .with_connect_timeout_fn(move |attempt| {
+                timed_attempts_clone.lock().unwrap().push(attempt);
+                if attempt == 1 {
+                    Duration::from_millis(20)
+                } else {
+                    Duration::from_secs(5)
+                }
+            });
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+
+        let next = tokio::time::timeout(Duration::from_secs(5), dummy.next()).await;
+        assert_eq!(next.unwrap().unwrap(), b"a".to_vec());
+        assert_eq!(*timed_attempts.lock().unwrap(), vec![0, 1, 2]);
+        assert_eq!(dummy.stats().failed_attempts, 1);
+    }
+}
+
+#[cfg(test)]
+mod endpoints {
+    use futures::stream::StreamExt;
+
+    use stream_reconnect::EndpointPolicy;
+
+    use super::*;
+
+    /// Connects to three endpoints, the first one failing, then disconnects once,
+    /// and returns the endpoints which were tried.
+    async fn attempted_endpoints(policy: EndpointPolicy) -> Vec<u8> {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![false, true, true])),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (Poll::Ready(Err(io::Error::other("eof"))), vec![]),
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+            ])),
+            ..DummyCtor::default()
+        };
+        let ctor_args = (0..3)
+            .map(|endpoint| DummyCtor {
+                endpoint,
+                ..ctor.clone()
+            })
+            .collect();
+
+        let options = ReconnectOptions::new()
+            .with_exit_if_first_connect_fails(false)
+            .with_retries_generator(|| vec![Duration::from_millis(10); 3])
+            .with_endpoint_policy(policy);
+
+        let mut dummy = ReconnectDummy::connect_with_endpoints(ctor_args, options)
+            .await
+            .unwrap();
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+
+        let attempted = ctor.attempted_endpoints.lock().unwrap().clone();
+        attempted
+    }
+
+    #[tokio::test]
+    async fn should_start_over_from_first_endpoint_on_failover() {
+        assert_eq!(
+            attempted_endpoints(EndpointPolicy::Failover).await,
+            vec![0, 1, 0]
+        );
+    }
+
+    #[tokio::test]
+    async fn should_move_on_to_next_endpoint_on_round_robin() {
+        assert_eq!(
+            attempted_endpoints(EndpointPolicy::RoundRobin).await,
+            vec![0, 1, 2]
+        );
+    }
+}
+
+#[cfg(test)]
+mod cooperative_establish {
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_not_block_other_tasks_while_establishing() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, true])),
+            connect_delays: Arc::new(Mutex::new(vec![Duration::ZERO, Duration::from_millis(100)])),
+            poll_read_results: Arc::new(Mutex::new(vec![(Poll::Ready(Ok(())), b"a".to_vec())])),
+            ..DummyCtor::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(10)]);
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+
+        // runs on the same thread as the stream, since tokio tests use a single-threaded runtime
+        let ticks = Arc::new(AtomicU8::new(0));
+        let ticks_clone = ticks.clone();
+        let ticker = tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                ticks_clone.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+
+        dummy.force_reconnect();
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+        assert!(ticks.load(Ordering::Relaxed) >= 5);
+        ticker.abort();
+    }
+}
+
+#[cfg(test)]
+mod classify_item {
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_drop_control_items_and_reconnect_on_sentinel() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![true, true]));
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (Poll::Ready(Ok(())), b"ping".to_vec()),
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+                (Poll::Ready(Ok(())), b"bye".to_vec()),
+                (Poll::Ready(Ok(())), b"b".to_vec()),
+            ])),
+            ..DummyCtor::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(10)]);
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+
+        let items: Vec<Vec<u8>> = dummy.by_ref().take(2).collect().await;
+        assert_eq!(items, vec![b"a".to_vec(), b"b".to_vec()]);
+        assert_eq!(dummy.stats().dropped_inbound, 1);
+        assert!(connect_outcomes.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_end_without_reconnecting_on_terminate() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![true, true]));
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+                (Poll::Ready(Ok(())), b"close".to_vec()),
+            ])),
+            ..DummyCtor::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(10)]);
+        let dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        let items: Vec<_> = dummy.collect().await;
+        assert_eq!(items, vec![b"a".to_vec()]);
+        assert_eq!(connect_outcomes.lock().unwrap().len(), 1);
+    }
+}
+
+#[cfg(test)]
+mod initial_jitter {
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_only_delay_first_attempt() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, false, true])),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (Poll::Ready(Err(io::Error::other("eof"))), vec![]),
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+            ])),
+            ..DummyCtor::default()
+        };
+
+        let delays: Arc<Mutex<Vec<Option<Duration>>>> = Arc::default();
+        let (disconnect_delays, fail_delays) = (delays.clone(), delays.clone());
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(10); 2])
+            .with_initial_jitter_seeded(Duration::from_millis(40), 42)
+            .with_on_disconnect_callback_ctx(move |ctx| {
+                disconnect_delays.lock().unwrap().push(ctx.next_delay)
+            })
+            .with_on_connect_fail_callback_ctx(move |ctx| {
+                fail_delays.lock().unwrap().push(ctx.next_delay)
+            });
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+
+        let delays = delays.lock().unwrap();
+        let first = delays[0].unwrap();
+        assert!(first >= Duration::from_millis(10) && first <= Duration::from_millis(50));
+        assert_eq!(delays[1], Some(Duration::from_millis(10)));
+    }
+}
+
+#[cfg(test)]
+mod reconfigure_clone {
+    use futures::stream::StreamExt;
+
+    use stream_reconnect::ConnectionStatus;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_connect_sibling_when_polled() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![true, true]));
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+                (Poll::Ready(Ok(())), b"b".to_vec()),
+            ])),
+            ..DummyCtor::default()
+        };
+
+        let mut dummy = ReconnectDummy::connect(ctor).await.unwrap();
+        let mut sibling = dummy.reconfigure_clone();
+        assert_eq!(sibling.status(), ConnectionStatus::Disconnected);
+        assert_eq!(connect_outcomes.lock().unwrap().len(), 1);
+
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+        assert_eq!(sibling.next().await.unwrap(), b"b".to_vec());
+        assert_eq!(sibling.status(), ConnectionStatus::Connected);
+        assert_eq!(dummy.status(), ConnectionStatus::Connected);
+        assert!(connect_outcomes.lock().unwrap().is_empty());
+    }
+}
+
+#[cfg(test)]
+mod reconnect_start {
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_fire_once_per_episode_and_not_while_paused() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, false, true])),
+            poll_read_results: Arc::new(Mutex::new(vec![(Poll::Ready(Ok(())), b"a".to_vec())])),
+            ..DummyCtor::default()
+        };
+
+        let starts = Arc::new(AtomicU8::new(0));
+        let starts_clone = starts.clone();
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(10); 2])
+            .with_on_reconnect_start_callback(move || {
+                starts_clone.fetch_add(1, Ordering::Relaxed);
+            });
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        dummy.pause();
+        dummy.force_reconnect();
+        let paused = tokio::time::timeout(Duration::from_millis(30), dummy.next()).await;
+        assert!(paused.is_err());
+        assert_eq!(starts.load(Ordering::Relaxed), 0);
+
+        dummy.resume();
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+        assert_eq!(starts.load(Ordering::Relaxed), 1);
+    }
+}
+
+#[cfg(test)]
+mod inbound_buffer {
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_stop_reading_while_the_buffer_is_full() {
+        let poll_read_results = Arc::new(Mutex::new(vec![
+            (Poll::Ready(Ok(())), b"a".to_vec()),
+            (Poll::Ready(Ok(())), b"b".to_vec()),
+            (Poll::Ready(Ok(())), b"c".to_vec()),
+            (Poll::Ready(Ok(())), b"d".to_vec()),
+            (Poll::Pending, vec![]),
+        ]));
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true])),
+            poll_read_results: poll_read_results.clone(),
+            ..DummyCtor::default()
+        };
+
+        let options = ReconnectOptions::new().with_inbound_buffer(2);
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+        assert_eq!(poll_read_results.lock().unwrap().len(), 3);
+        assert_eq!(dummy.next().await.unwrap(), b"b".to_vec());
+        assert_eq!(poll_read_results.lock().unwrap().len(), 2);
+        assert_eq!(dummy.next().await.unwrap(), b"c".to_vec());
+        assert_eq!(dummy.next().await.unwrap(), b"d".to_vec());
+    }
+}
+
+#[cfg(test)]
+mod never_reconnect {
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_die_on_the_first_disconnect() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![true, true]));
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+                (
+                    Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "fatal",
+                    ))),
+                    vec![],
+                ),
+            ])),
+            ..DummyCtor::default()
+        };
+
+        let disconnects = Arc::new(AtomicU8::new(0));
+        let disconnects_clone = disconnects.clone();
+        let options = ReconnectOptions::never_reconnect().with_on_disconnect_callback(move || {
+            disconnects_clone.fetch_add(1, Ordering::Relaxed);
+        });
+        assert!(options.validate().is_ok());
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+        assert!(dummy.reconnection_enabled());
+        assert!(dummy.next().await.is_none());
+        assert!(dummy.is_dead());
+        assert!(!dummy.reconnection_enabled());
+        assert_eq!(disconnects.load(Ordering::Relaxed), 1);
+        assert_eq!(dummy.stats().failed_attempts, 0);
+        assert_eq!(connect_outcomes.lock().unwrap().len(), 1);
+    }
+}
+
+#[cfg(test)]
+mod get_ref {
+    use futures::future::FutureExt;
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_expose_the_underlying_stream_only_while_connected() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, false])),
+            poll_read_results: Arc::new(Mutex::new(vec![(
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "fatal",
+                ))),
+                vec![],
+            )])),
+            ..DummyCtor::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(10)]);
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert!(dummy.get_ref().is_some());
+        assert!(dummy.get_mut().is_some());
+
+        assert!(dummy.next().await.is_none());
+        assert!(dummy.get_ref().is_none());
+        assert!(dummy.get_mut().is_none());
+    }
+
+    #[tokio::test]
+    async fn should_drop_the_underlying_stream_once_a_read_disconnect_is_detected() {
+        let poll_read_results = Arc::new(Mutex::new(vec![(
+            Poll::Ready(Err(io::Error::new(io::ErrorKind::ConnectionReset, "reset"))),
+            vec![],
+        )]));
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true])),
+            poll_read_results: poll_read_results.clone(),
+            ..DummyCtor::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_secs(10)]);
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        // held by this test, the ctor arg and the underlying stream
+        assert_eq!(Arc::strong_count(&poll_read_results), 3);
+
+        assert!(dummy.next().now_or_never().is_none());
+        assert!(dummy.get_ref().is_none());
+        // the scheduled attempt holds a clone of the ctor arg in place of the dropped stream
+        assert_eq!(Arc::strong_count(&poll_read_results), 3);
+    }
+}
+
+#[cfg(test)]
+mod circuit_breaker {
+    use futures::stream::StreamExt;
+    use futures::SinkExt;
+
+    use stream_reconnect::{ConnectionStatus, ReconnectError};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_reject_sends_while_open_and_close_on_successful_probe() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![true, false, false, false, true]));
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (
+                    Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "fatal",
+                    ))),
+                    vec![],
+                ),
+                (Poll::Pending, vec![]),
+                (Poll::Pending, vec![]),
+                (Poll::Pending, vec![]),
+                (Poll::Pending, vec![]),
+            ])),
+            ..DummyCtor::default()
+        };
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(10); 10])
+            .with_circuit_breaker(2, Duration::from_millis(50));
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert!(futures::poll!(dummy.next()).is_pending());
+
+        let _ = tokio::time::timeout(Duration::from_millis(40), dummy.next()).await;
+        assert!(matches!(
+            dummy.status(),
+            ConnectionStatus::CircuitOpen { .. }
+        ));
+        assert!(matches!(
+            dummy.send(b"a".to_vec()).await,
+            Err(ReconnectError::CircuitOpen)
+        ));
+
+        // the first probe fails and the circuit stays open, the second one closes it
+        let _ = tokio::time::timeout(Duration::from_millis(70), dummy.next()).await;
+        assert!(matches!(
+            dummy.status(),
+            ConnectionStatus::CircuitOpen { .. }
+        ));
+        let _ = tokio::time::timeout(Duration::from_millis(70), dummy.next()).await;
+        assert_eq!(dummy.status(), ConnectionStatus::Connected);
+        assert_eq!(dummy.stats().failed_attempts, 3);
+        assert!(connect_outcomes.lock().unwrap().is_empty());
+    }
+}
+
+#[cfg(test)]
+mod attempt_rate_limit {
+    use std::time::Instant;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_postpone_attempts_beyond_the_limit() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![false, false, true]));
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            ..DummyCtor::default()
+        };
+
+        let options = ReconnectOptions::new()
+            .with_exit_if_first_connect_fails(false)
+            .with_retries_generator(|| vec![Duration::from_millis(10); 3])
+            .with_attempt_rate_limit(2, Duration::from_millis(200));
+
+        let started_at = Instant::now();
+        ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert!(started_at.elapsed() >= Duration::from_millis(200));
+        assert!(connect_outcomes.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_count_migrations() {
+        let ctor = || DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true])),
+            ..DummyCtor::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_attempt_rate_limit(1, Duration::from_millis(200));
+
+        let started_at = Instant::now();
+        let mut dummy = ReconnectDummy::connect_with_options(ctor(), options)
+            .await
+            .unwrap();
+        dummy.migrate::<Vec<u8>>(ctor()).await.unwrap();
+        assert!(started_at.elapsed() >= Duration::from_millis(200));
+    }
+}
+
+#[cfg(test)]
+mod events {
+    use futures::stream::StreamExt;
+
+    use stream_reconnect::ConnEvent;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_yield_transitions_until_dropped() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, false, true])),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (
+                    Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "fatal",
+                    ))),
+                    vec![],
+                ),
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+            ])),
+            ..DummyCtor::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(10); 2]);
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        let events = dummy.events();
+
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+        drop(dummy);
+        assert_eq!(
+            events.collect::<Vec<_>>().await,
+            vec![
+                ConnEvent::Disconnected,
+                ConnEvent::ReconnectFailed,
+                ConnEvent::Connected
+            ]
+        );
+    }
+}
+
+#[cfg(test)]
+mod initial_retries {
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_use_the_initial_generator_only_for_the_first_connection() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![false, false, true, true]));
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            poll_read_results: Arc::new(Mutex::new(vec![(
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "fatal",
+                ))),
+                vec![],
+            )])),
+            ..DummyCtor::default()
+        };
+
+        let options = ReconnectOptions::new()
+            .with_exit_if_first_connect_fails(false)
+            .with_initial_retries_generator(|| vec![Duration::from_millis(10); 2])
+            .with_retries_generator(Vec::new);
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert_eq!(dummy.stats().failed_attempts, 2);
+
+        assert!(dummy.next().await.is_none());
+        assert!(dummy.is_dead());
+        assert_eq!(connect_outcomes.lock().unwrap().len(), 1);
+    }
+}
+
+#[cfg(test)]
+mod close {
+    use futures::stream::StreamExt;
+    use futures::SinkExt;
+
+    use stream_reconnect::{ConnectionStatus, ReconnectError};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_flush_and_shut_down_instead_of_reconnecting() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![true, true]));
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            ..DummyCtor::default()
+        };
+        let sent_items = ctor.sent_items.clone();
+
+        let mut dummy = ReconnectDummy::connect(ctor).await.unwrap();
+        dummy.feed(b"a".to_vec()).await.unwrap();
+        dummy.close().await.unwrap();
+
+        assert_eq!(*sent_items.lock().unwrap(), vec![b"a".to_vec()]);
+        assert_eq!(dummy.status(), ConnectionStatus::Shutdown);
+        assert!(!dummy.reconnection_enabled());
+        assert!(dummy.next().await.is_none());
+        assert!(matches!(
+            dummy.send(b"b".to_vec()).await,
+            Err(ReconnectError::Shutdown)
+        ));
+        assert_eq!(connect_outcomes.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn should_keep_buffered_items_when_closed_while_disconnected() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, true])),
+            poll_read_results: Arc::new(Mutex::new(vec![(
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "fatal",
+                ))),
+                vec![],
+            )])),
+            ..DummyCtor::default()
+        };
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(50)])
+            .with_outbound_buffer(2);
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert!(futures::poll!(dummy.next()).is_pending());
+        dummy.feed(b"a".to_vec()).await.unwrap();
+
+        dummy.close().await.unwrap();
+        assert_eq!(dummy.status(), ConnectionStatus::Shutdown);
+        assert_eq!(dummy.take_pending::<Vec<u8>>(), vec![b"a".to_vec()]);
+    }
+}
+
+#[cfg(test)]
+mod backoff_fn {
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_compute_delays_from_the_attempt_index() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, false, false])),
+            poll_read_results: Arc::new(Mutex::new(vec![(
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "fatal",
+                ))),
+                vec![],
+            )])),
+            ..DummyCtor::default()
+        };
+
+        let delays = Arc::new(Mutex::new(vec![]));
+        let disconnect_delays = delays.clone();
+        let fail_delays = delays.clone();
+        let options = ReconnectOptions::new()
+            .with_backoff_fn(|attempt| Duration::from_millis(10 * (attempt as u64 + 1)))
+            .with_max_attempts(2)
+            .with_on_disconnect_callback_ctx(move |ctx| {
+                disconnect_delays.lock().unwrap().push(ctx.next_delay)
+            })
+            .with_on_connect_fail_callback_ctx(move |ctx| {
+                fail_delays.lock().unwrap().push(ctx.next_delay)
+            });
+        assert!(options.validate().is_ok());
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert!(dummy.next().await.is_none());
+        assert!(dummy.is_dead());
+        assert_eq!(
+            *delays.lock().unwrap(),
+            vec![
+                Some(Duration::from_millis(10)),
+                Some(Duration::from_millis(20)),
+                None
+            ]
+        );
+    }
+}
+
+#[cfg(test)]
+mod should_continue {
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_give_up_once_the_predicate_returns_false() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![true, false, false, true]));
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            poll_read_results: Arc::new(Mutex::new(vec![(
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "fatal",
+                ))),
+                vec![],
+            )])),
+            ..DummyCtor::default()
+        };
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(10); 5])
+            .with_should_continue(|attempt, _| attempt < 2);
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert!(dummy.next().await.is_none());
+        assert!(dummy.is_dead());
+        assert_eq!(dummy.stats().failed_attempts, 2);
+        assert_eq!(connect_outcomes.lock().unwrap().len(), 1);
+    }
+}
+
+#[cfg(test)]
+mod wait_connected {
+    use futures::stream::StreamExt;
+
+    use stream_reconnect::{ConnectionStatus, ReconnectError};
+
+    use super::*;
+
+    fn disconnecting_ctor(connect_outcomes: Vec<bool>) -> DummyCtor {
+        DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(connect_outcomes)),
+            poll_read_results: Arc::new(Mutex::new(vec![(
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "fatal",
+                ))),
+                vec![],
+            )])),
+            ..DummyCtor::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn should_resolve_once_reconnected() {
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(10); 2]);
+        let mut dummy = ReconnectDummy::connect_with_options(
+            disconnecting_ctor(vec![true, false, true]),
+            options,
+        )
+        .await
+        .unwrap();
+        assert!(futures::poll!(dummy.next()).is_pending());
+
+        dummy.wait_connected().await.unwrap();
+        assert_eq!(dummy.status(), ConnectionStatus::Connected);
+    }
+
+    #[tokio::test]
+    async fn should_fail_once_exhausted() {
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(10)]);
+        let mut dummy =
+            ReconnectDummy::connect_with_options(disconnecting_ctor(vec![true, false]), options)
+                .await
+                .unwrap();
+        assert!(futures::poll!(dummy.next()).is_pending());
+
+        assert!(matches!(
+            dummy.wait_connected().await,
+            Err(ReconnectError::ReconnectsExhausted)
+        ));
+    }
+}
+
+#[cfg(test)]
+mod split_halves {
+    use futures::stream::StreamExt;
+    use futures::SinkExt;
+
+    use stream_reconnect::{ConnectionStatus, ReconnectError};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_interrupt_a_pending_read_on_write_disconnect() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![true, true]));
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (Poll::Pending, vec![]),
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+            ])),
+            flush_results: Arc::new(Mutex::new(vec![Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "fatal",
+            ))])),
+            ..DummyCtor::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(10)]);
+        let dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        let (mut writer, mut reader) = dummy.split();
+
+        // the read waits on the first connection, which never wakes it up by itself
+        let read = tokio::spawn(async move { reader.next().await });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        writer.send(b"x".to_vec()).await.unwrap();
+        let item = tokio::time::timeout(Duration::from_secs(1), read)
+            .await
+            .expect("the pending read was not interrupted")
+            .unwrap();
+        assert_eq!(item, Some(b"a".to_vec()));
+        assert!(connect_outcomes.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_reconnect_the_write_half_on_read_disconnect() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![true, true]));
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (
+                    Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "fatal",
+                    ))),
+                    vec![],
+                ),
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+            ])),
+            ..DummyCtor::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(10)]);
+        let dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        let (mut writer, mut reader) = dummy.split();
+
+        assert_eq!(reader.next().await, Some(b"a".to_vec()));
+        assert!(connect_outcomes.lock().unwrap().is_empty());
+        assert_eq!(writer.lock().status(), ConnectionStatus::Connected);
+        writer.send(b"x".to_vec()).await.unwrap();
+
+        let dummy = writer.reunite(reader).unwrap();
+        assert_eq!(dummy.stats().reconnects, 1);
+    }
+
+    #[tokio::test]
+    async fn should_reject_a_send_after_the_read_half_disconnected_since_poll_ready() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true])),
+            poll_read_results: Arc::new(Mutex::new(vec![(
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "fatal",
+                ))),
+                vec![],
+            )])),
+            ..DummyCtor::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_secs(10)]);
+        let dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        let (mut writer, mut reader) = dummy.split();
+
+        futures::future::poll_fn(|cx| Pin::new(&mut writer).poll_ready(cx))
+            .await
+            .unwrap();
+        assert!(futures::poll!(reader.next()).is_pending());
+        assert!(matches!(
+            Pin::new(&mut writer).start_send(b"x".to_vec()),
+            Err(ReconnectError::Disconnected)
+        ));
+    }
+}
+
+#[cfg(test)]
+mod fail_fast_send {
+    use futures::stream::StreamExt;
+    use futures::SinkExt;
+
+    use stream_reconnect::ReconnectError;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_reject_sends_while_disconnected() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![true, false, true]));
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (
+                    Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "fatal",
+                    ))),
+                    vec![],
+                ),
+                (Poll::Pending, vec![]),
+            ])),
+            ..DummyCtor::default()
+        };
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(10); 2])
+            .with_fail_fast_send(true);
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert!(futures::poll!(dummy.next()).is_pending());
+
+        assert!(matches!(
+            dummy.send(b"a".to_vec()).await,
+            Err(ReconnectError::Disconnected)
+        ));
+
+        dummy.wait_connected().await.unwrap();
+        dummy.send(b"b".to_vec()).await.unwrap();
+        assert!(connect_outcomes.lock().unwrap().is_empty());
+    }
+}
+
+#[cfg(test)]
+mod first_connect_grace {
+    use stream_reconnect::ReconnectError;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_connect_within_the_grace() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![false, false, true])),
+            ..DummyCtor::default()
+        };
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(10); 5])
+            .with_first_connect_grace(3);
+        let dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert_eq!(dummy.stats().failed_attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn should_bail_once_the_grace_is_exhausted() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![false, false, true]));
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            ..DummyCtor::default()
+        };
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(10); 5])
+            .with_first_connect_grace(2);
+        let result = ReconnectDummy::connect_with_options(ctor, options).await;
+        assert!(matches!(
+            result,
+            Err(ReconnectError::InitialConnectFailed(_))
+        ));
+        assert_eq!(connect_outcomes.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn should_make_a_single_attempt_with_a_zero_grace() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![false, true]));
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            ..DummyCtor::default()
+        };
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(10); 5])
+            .with_first_connect_grace(0);
+        let result = ReconnectDummy::connect_with_options(ctor, options).await;
+        assert!(matches!(
+            result,
+            Err(ReconnectError::InitialConnectFailed(_))
+        ));
+        assert_eq!(*connect_outcomes.lock().unwrap(), vec![true]);
+    }
+}
+
+#[cfg(test)]
+mod establish_once {
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_hand_off_the_bare_stream_after_retrying() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![false, false, true]));
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            poll_read_results: Arc::new(Mutex::new(vec![(Poll::Ready(Ok(())), b"a".to_vec())])),
+            ..DummyCtor::default()
+        };
+
+        let options = ReconnectOptions::new()
+            .with_exit_if_first_connect_fails(false)
+            .with_retries_generator(|| vec![Duration::from_millis(10); 2]);
+        let mut stream: DummyStream = ReconnectDummy::establish_once(ctor, options).await.unwrap();
+        assert!(connect_outcomes.lock().unwrap().is_empty());
+        assert_eq!(stream.next().await.unwrap(), b"a".to_vec());
+    }
+}
+
+#[cfg(test)]
+mod on_connect_async {
+    use std::sync::atomic::AtomicBool;
+
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_complete_before_items_of_the_new_connection() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, true])),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (
+                    Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "fatal",
+                    ))),
+                    vec![],
+                ),
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+            ])),
+            ..DummyCtor::default()
+        };
+
+        let connects = Arc::new(AtomicU8::new(0));
+        let subscribed = Arc::new(AtomicBool::new(false));
+        let connects_clone = connects.clone();
+        let subscribed_clone = subscribed.clone();
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(10)])
+            .with_on_connect_callback(move || {
+                connects_clone.fetch_add(1, Ordering::Relaxed);
+            })
+            .with_on_connect_async(move |_| {
+                let subscribed = subscribed_clone.clone();
+                async move {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    subscribed.store(true, Ordering::Relaxed);
+                }
+            });
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert!(subscribed.swap(false, Ordering::Relaxed));
+
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+        assert_eq!(connects.load(Ordering::Relaxed), 2);
+        assert!(subscribed.load(Ordering::Relaxed));
+    }
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod test_clock {
+    use futures::stream::StreamExt;
+
+    use stream_reconnect::TestClock;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_follow_the_backoff_schedule_of_the_clock() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![true, false, true]));
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (
+                    Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "fatal",
+                    ))),
+                    vec![],
+                ),
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+            ])),
+            ..DummyCtor::default()
+        };
+
+        let clock = TestClock::new();
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_secs(600), Duration::from_secs(1200)]);
+        let mut dummy = ReconnectDummy::with_clock(ctor, options, clock.clone())
+            .await
+            .unwrap();
+        assert!(futures::poll!(dummy.next()).is_pending());
+        assert_eq!(dummy.next_retry_in(), Some(Duration::from_secs(600)));
+
+        clock.advance(Duration::from_secs(599));
+        assert!(futures::poll!(dummy.next()).is_pending());
+        assert_eq!(dummy.next_retry_in(), Some(Duration::from_secs(1)));
+        assert_eq!(dummy.stats().failed_attempts, 0);
+
+        clock.advance(Duration::from_secs(1));
+        assert!(futures::poll!(dummy.next()).is_pending());
+        assert_eq!(dummy.stats().failed_attempts, 1);
+        assert_eq!(dummy.next_retry_in(), Some(Duration::from_secs(1200)));
+
+        clock.advance(Duration::from_secs(1200));
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+        assert!(connect_outcomes.lock().unwrap().is_empty());
+        assert_eq!(dummy.stats().downtime, Duration::from_secs(1800));
+    }
+}
+
+#[cfg(test)]
+mod fatal_establish_error {
+    use futures::stream::StreamExt;
+
+    use stream_reconnect::ReconnectError;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_not_retry_the_initial_connect() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![false, true]));
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            connect_error_kinds: Arc::new(Mutex::new(vec![ErrorKind::PermissionDenied])),
+            ..DummyCtor::default()
+        };
+
+        let options = ReconnectOptions::new()
+            .with_exit_if_first_connect_fails(false)
+            .with_retries_generator(|| vec![Duration::from_millis(10)]);
+        let result = ReconnectDummy::connect_with_options(ctor, options).await;
+        assert!(matches!(
+            result,
+            Err(ReconnectError::InitialConnectFailed(err)) if err.kind() == ErrorKind::PermissionDenied
+        ));
+        assert_eq!(connect_outcomes.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn should_die_instead_of_reconnecting() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![true, false, false, true]));
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            connect_error_kinds: Arc::new(Mutex::new(vec![
+                ErrorKind::NotConnected,
+                ErrorKind::PermissionDenied,
+            ])),
+            poll_read_results: Arc::new(Mutex::new(vec![(
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "fatal",
+                ))),
+                vec![],
+            )])),
+            ..DummyCtor::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(10); 5]);
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert!(dummy.next().await.is_none());
+        assert!(dummy.is_dead());
+        assert_eq!(dummy.stats().failed_attempts, 2);
+        assert_eq!(connect_outcomes.lock().unwrap().len(), 1);
+    }
+}
+
+#[cfg(test)]
+mod from_connected {
+    use futures::stream::StreamExt;
+
+    use stream_reconnect::ConnectionStatus;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_only_establish_upon_reconnect() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![true]));
+        let poll_read_results = Arc::new(Mutex::new(vec![
+            (Poll::Ready(Ok(())), b"a".to_vec()),
+            (
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "fatal",
+                ))),
+                vec![],
+            ),
+            (Poll::Ready(Ok(())), b"b".to_vec()),
+        ]));
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            poll_read_results: poll_read_results.clone(),
+            ..DummyCtor::default()
+        };
+        let stream = DummyStream {
+            poll_read_results,
+            ..DummyStream::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(10)]);
+        let mut dummy = ReconnectDummy::from_connected(stream, ctor, options);
+        assert_eq!(dummy.status(), ConnectionStatus::Connected);
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+        assert_eq!(connect_outcomes.lock().unwrap().len(), 1);
+
+        assert_eq!(dummy.next().await.unwrap(), b"b".to_vec());
+        assert!(connect_outcomes.lock().unwrap().is_empty());
+        assert_eq!(dummy.stats().reconnects, 1);
+    }
+}
+
+#[cfg(test)]
+mod item_hooks {
+    use futures::stream::StreamExt;
+    use futures::SinkExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_tap_items_but_not_control_items() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true])),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (Poll::Ready(Ok(())), b"ping".to_vec()),
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+                (Poll::Pending, vec![]),
+            ])),
+            ..DummyCtor::default()
+        };
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let received_clone = received.clone();
+        let sent_clone = sent.clone();
+        let options = ReconnectOptions::new()
+            .with_on_item_received(move |item: &Vec<u8>| {
+                received_clone.lock().unwrap().push(item.clone())
+            })
+            .with_on_item_sent(move |item: &Vec<u8>| sent_clone.lock().unwrap().push(item.clone()));
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+        dummy.send(b"x".to_vec()).await.unwrap();
+        assert_eq!(*received.lock().unwrap(), vec![b"a".to_vec()]);
+        assert_eq!(*sent.lock().unwrap(), vec![b"x".to_vec()]);
+    }
+}
+
+#[cfg(test)]
+mod min_delay {
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_raise_jittered_delays_to_the_floor() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, false])),
+            poll_read_results: Arc::new(Mutex::new(vec![(
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "fatal",
+                ))),
+                vec![],
+            )])),
+            ..DummyCtor::default()
+        };
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(1); 3])
+            .with_full_jitter()
+            .with_min_delay(Duration::from_secs(600));
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert!(futures::poll!(dummy.next()).is_pending());
+
+        let delay = dummy.next_retry_in().unwrap();
+        assert!(delay > Duration::from_secs(599));
+        assert!(delay <= Duration::from_secs(600));
+    }
+}
+
+#[cfg(test)]
+mod paired {
+    use futures::stream::StreamExt;
+
+    use stream_reconnect::ConnectionStatus;
+
+    use super::*;
+
+    fn ctor(poll_read_results: Vec<(Poll<io::Result<()>>, Vec<u8>)>) -> DummyCtor {
+        DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, true])),
+            poll_read_results: Arc::new(Mutex::new(poll_read_results)),
+            ..DummyCtor::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn should_reconnect_both_streams_with_a_shared_backoff() {
+        let first_ctor = ctor(vec![
+            (
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "fatal",
+                ))),
+                vec![],
+            ),
+            (Poll::Ready(Ok(())), b"a".to_vec()),
+        ]);
+        let second_ctor = ctor(vec![(Poll::Ready(Ok(())), b"b".to_vec())]);
+        let second_outcomes = second_ctor.connect_outcomes.clone();
+
+        let first = ReconnectDummy::connect_with_options(
+            first_ctor,
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(50)]),
+        )
+        .await
+        .unwrap();
+        let second = ReconnectDummy::connect_with_options(
+            second_ctor,
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_secs(600)]),
+        )
+        .await
+        .unwrap();
+        let (mut first, mut second) = first.pair_with(second);
+
+        assert!(futures::poll!(first.next()).is_pending());
+        assert!(futures::poll!(second.next()).is_pending());
+        assert_eq!(second.status(), ConnectionStatus::Disconnected);
+        assert_eq!(second.stats().disconnects, 1);
+        assert_eq!(second.next_retry_in(), None);
+
+        assert_eq!(first.next().await.unwrap(), b"a".to_vec());
+        let item = tokio::time::timeout(Duration::from_secs(1), second.next())
+            .await
+            .expect("the second stream waited for its own backoff");
+        assert_eq!(item.unwrap(), b"b".to_vec());
+        assert!(second_outcomes.lock().unwrap().is_empty());
+        assert_eq!(second.stats().failed_attempts, 0);
+        assert_eq!(second.stats().reconnects, 1);
+    }
+
+    #[tokio::test]
+    async fn should_give_up_along_with_the_paired_stream() {
+        let first_ctor = ctor(vec![(
+            Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "fatal",
+            ))),
+            vec![],
+        )]);
+        let second_ctor = ctor(vec![(Poll::Pending, vec![])]);
+
+        let options = ReconnectOptions::new().with_retries_generator(Vec::new);
+        let first = ReconnectDummy::connect_with_options(first_ctor, options.clone())
+            .await
+            .unwrap();
+        let second = ReconnectDummy::connect_with_options(second_ctor, options)
+            .await
+            .unwrap();
+        let (mut first, mut second) = first.pair_with(second);
+
+        assert!(first.next().await.is_none());
+        assert!(first.is_dead());
+        assert!(second.next().await.is_none());
+        assert!(second.is_dead());
+    }
+}
+
+#[cfg(test)]
+mod max_delay {
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    async fn retry_in(options: ReconnectOptions) -> Option<Duration> {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, false])),
+            poll_read_results: Arc::new(Mutex::new(vec![(
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "fatal",
+                ))),
+                vec![],
+            )])),
+            ..DummyCtor::default()
+        };
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert!(futures::poll!(dummy.next()).is_pending());
+        dummy.next_retry_in()
+    }
+
+    #[tokio::test]
+    async fn should_cap_huge_delays() {
+        let day = Duration::from_secs(24 * 60 * 60);
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::MAX])
+            .with_initial_jitter(Duration::from_secs(1))
+            .with_attempt_rate_limit(1, Duration::from_secs(1));
+        let delay = retry_in(options).await.unwrap();
+        assert!(delay > day - Duration::from_secs(1));
+        assert!(delay <= day);
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::MAX])
+            .with_max_delay(Duration::from_secs(60));
+        let delay = retry_in(options).await.unwrap();
+        assert!(delay > Duration::from_secs(59));
+        assert!(delay <= Duration::from_secs(60));
+    }
+}
+
+#[cfg(test)]
+mod cancel_token {
+    use stream_reconnect::{CancelToken, ReconnectError};
+
+    use super::*;
+
+    type Tokens = Arc<Mutex<Vec<CancelToken>>>;
+
+    struct HangingConnector;
+
+    impl UnderlyingStream<Tokens, Vec<u8>, io::Error> for HangingConnector {
+        type Stream = DummyStream;
+
+        #[cfg(not(feature = "not-send"))]
+        async fn establish(_tokens: Tokens) -> io::Result<DummyStream> {
+            unreachable!("the attempts are established with a cancel token")
+        }
+
+        #[cfg(not(feature = "not-send"))]
+        async fn establish_with_cancel(
+            tokens: Tokens,
+            cancel: CancelToken,
+        ) -> io::Result<DummyStream> {
+            tokens.lock().unwrap().push(cancel);
+            futures::future::pending().await
+        }
+
+        fn is_write_disconnect_error(_err: &Error) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn should_cancel_a_timed_out_attempt() {
+        let tokens = Tokens::default();
+        let options = ReconnectOptions::new().with_connect_timeout(Duration::from_millis(10));
+        let result =
+            ReconnectStream::<HangingConnector, _, Vec<u8>, io::Error>::connect_with_options(
+                tokens.clone(),
+                options,
+            )
+            .await;
+        assert!(matches!(result, Err(ReconnectError::ConnectTimeout)));
+
+        let token = tokens.lock().unwrap().pop().unwrap();
+        assert!(token.is_cancelled());
+        token.cancelled().await;
+    }
+}
+
+#[cfg(test)]
+mod disconnect_error_threshold {
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_tolerate_disconnect_errors_below_the_threshold() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, true])),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (Poll::Ready(Ok(())), b"err".to_vec()),
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+                (Poll::Ready(Ok(())), b"err".to_vec()),
+                (Poll::Ready(Ok(())), b"err".to_vec()),
+                (Poll::Ready(Ok(())), b"b".to_vec()),
+                (Poll::Ready(Ok(())), b"err".to_vec()),
+                (Poll::Ready(Ok(())), b"err".to_vec()),
+                (Poll::Ready(Ok(())), b"err".to_vec()),
+                (Poll::Ready(Ok(())), b"c".to_vec()),
+            ])),
+            ..DummyCtor::default()
+        };
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(10)])
+            .with_disconnect_error_threshold(3);
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+        assert_eq!(dummy.next().await.unwrap(), b"b".to_vec());
+        assert_eq!(dummy.stats().disconnects, 0);
+        assert_eq!(dummy.next().await.unwrap(), b"c".to_vec());
+        assert_eq!(dummy.stats().disconnects, 1);
+    }
+}
+
+#[cfg(all(test, feature = "catch-panic"))]
+mod catch_panic {
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    #[derive(Clone)]
+    struct PanickyCtor {
+        attempts: Arc<AtomicU8>,
+        poll_read_results: PollReadResults,
+    }
+
+    struct PanickyConnector;
+
+    impl UnderlyingStream<PanickyCtor, Vec<u8>, io::Error> for PanickyConnector {
+        type Stream = DummyStream;
+
+        #[cfg(not(feature = "not-send"))]
+        async fn establish(ctor: PanickyCtor) -> io::Result<DummyStream> {
+            if ctor.attempts.fetch_add(1, Ordering::SeqCst) == 1 {
+                panic!("boom");
+            }
+            Ok(DummyStream {
+                poll_read_results: ctor.poll_read_results,
+                ..DummyStream::default()
+            })
+        }
+
+        fn is_write_disconnect_error(_err: &Error) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn should_retry_after_a_panicking_attempt() {
+        let ctor = PanickyCtor {
+            attempts: Arc::default(),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (
+                    Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "fatal",
+                    ))),
+                    vec![],
+                ),
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+            ])),
+        };
+        let errors = Arc::new(Mutex::new(Vec::new()));
+
+        let errors_clone = errors.clone();
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(10); 2])
+            .with_on_connect_fail_callback_err(move |err| {
+                errors_clone.lock().unwrap().push(err.to_string())
+            });
+        let mut dummy =
+            ReconnectStream::<PanickyConnector, _, Vec<u8>, io::Error>::connect_with_options(
+                ctor, options,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+        assert_eq!(dummy.stats().failed_attempts, 1);
+        assert_eq!(
+            *errors.lock().unwrap(),
+            vec!["connect attempt panicked: boom".to_string()]
+        );
+    }
+}
+
+#[cfg(test)]
+mod disconnect_kind {
+    use futures::stream::StreamExt;
+    use futures::SinkExt;
+
+    use stream_reconnect::DisconnectKind;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_report_what_caused_the_last_disconnect() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, true, true, true])),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (Poll::Ready(Ok(())), b"err".to_vec()),
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+                (Poll::Ready(Ok(())), b"b".to_vec()),
+            ])),
+            flush_results: Arc::new(Mutex::new(vec![Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "fatal",
+            ))])),
+            ..DummyCtor::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(10)]);
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert_eq!(dummy.last_disconnect_kind(), None);
+
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+        assert_eq!(dummy.last_disconnect_kind(), Some(DisconnectKind::Read));
+
+        dummy.force_reconnect();
+        assert_eq!(dummy.last_disconnect_kind(), Some(DisconnectKind::Forced));
+
+        dummy.send(b"x".to_vec()).await.unwrap();
+        assert_eq!(dummy.last_disconnect_kind(), Some(DisconnectKind::Write));
+        assert_eq!(dummy.stats().disconnects, 3);
+    }
+}
+
+#[cfg(test)]
+mod callback_control {
+    use futures::stream::StreamExt;
+
+    use stream_reconnect::{ControlFlow, ReconnectError};
+
+    use super::*;
+
+    fn dropped_once() -> DummyCtor {
+        DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, true])),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+                (
+                    Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "fatal",
+                    ))),
+                    vec![],
+                ),
+            ])),
+            ..DummyCtor::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn should_die_when_the_disconnect_callback_terminates() {
+        let ctor = dropped_once();
+        let connect_outcomes = ctor.connect_outcomes.clone();
+        let disconnects = Arc::new(AtomicU8::new(0));
+        let disconnects_clone = disconnects.clone();
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(10)])
+            .with_on_disconnect_callback_with_control(|ctx| {
+                assert_eq!(ctx.next_delay, None);
+                ControlFlow::Terminate
+            })
+            .with_on_disconnect_callback(move || {
+                disconnects_clone.fetch_add(1, Ordering::Relaxed);
+            });
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+        assert!(dummy.next().await.is_none());
+        assert!(dummy.is_dead());
+        assert_eq!(disconnects.load(Ordering::Relaxed), 1);
+        assert_eq!(connect_outcomes.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn should_die_when_the_connect_callback_terminates() {
+        let ctor = dropped_once();
+        let connect_outcomes = ctor.connect_outcomes.clone();
+        let connects = Arc::new(AtomicU8::new(0));
+        let connects_clone = connects.clone();
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(10)])
+            .with_on_connect_callback_with_control(move |_| {
+                if connects_clone.fetch_add(1, Ordering::Relaxed) == 0 {
+                    ControlFlow::Continue
+                } else {
+                    ControlFlow::Terminate
+                }
+            });
+
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+        assert!(dummy.next().await.is_none());
+        assert!(dummy.is_dead());
+        assert_eq!(connects.load(Ordering::Relaxed), 2);
+        assert!(connect_outcomes.lock().unwrap().is_empty());
+        assert_eq!(dummy.stats().reconnects, 0);
+    }
+
+    #[tokio::test]
+    async fn should_fail_the_initial_connect_when_the_connect_callback_terminates() {
+        let options = ReconnectOptions::new()
+            .with_on_connect_callback_with_control(|_| ControlFlow::Terminate);
+        let result = ReconnectDummy::connect_with_options(dropped_once(), options).await;
+        assert!(matches!(result, Err(ReconnectError::Terminated)));
+    }
+}
+
+#[cfg(test)]
+mod boxed {
+    use futures::stream::StreamExt;
+    use futures::SinkExt;
+
+    use stream_reconnect::{BoxedReconnectStream, ConnectionStatus};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_reconnect_through_the_boxed_stream() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![true, true]));
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+                (Poll::Ready(Ok(())), b"b".to_vec()),
+            ])),
+            ..DummyCtor::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(10)]);
+        let dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        let mut streams: Vec<BoxedReconnectStream<Vec<u8>, io::Error>> = vec![dummy.boxed()];
+        let stream = &mut streams[0];
+
+        assert_eq!(stream.next().await.unwrap(), b"a".to_vec());
+        stream.force_reconnect();
+        assert_eq!(
+            stream.status(),
+            ConnectionStatus::Reconnecting { attempt: 1 }
+        );
+        assert_eq!(stream.next().await.unwrap(), b"b".to_vec());
+        stream.send(b"x".to_vec()).await.unwrap();
+        assert_eq!(stream.stats().reconnects, 1);
+        assert!(connect_outcomes.lock().unwrap().is_empty());
+    }
+}
+
+#[cfg(test)]
+mod flush_timeout {
+    use futures::SinkExt;
+
+    use stream_reconnect::DisconnectKind;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_reconnect_when_a_flush_is_stuck() {
+        let connect_outcomes = Arc::new(Mutex::new(vec![true, true]));
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            stuck_flushes: Arc::new(Mutex::new(1)),
+            ..DummyCtor::default()
+        };
+        let sent_items = ctor.sent_items.clone();
+
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(10)])
+            .with_flush_timeout(Duration::from_millis(50));
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(1), dummy.send(b"x".to_vec()))
+            .await
+            .expect("the stuck flush did not time out")
+            .unwrap();
+        assert_eq!(
+            dummy.last_disconnect_kind(),
+            Some(DisconnectKind::FlushTimeout)
+        );
+        assert_eq!(dummy.stats().reconnects, 1);
+        assert!(connect_outcomes.lock().unwrap().is_empty());
+
+        dummy.send(b"y".to_vec()).await.unwrap();
+        assert_eq!(
+            *sent_items.lock().unwrap(),
+            vec![b"x".to_vec(), b"y".to_vec()]
+        );
+    }
+}
+
+#[cfg(test)]
+mod give_up_callback {
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    type Summaries = Arc<Mutex<Vec<(usize, Option<String>)>>>;
+
+    fn options(summaries: &Summaries) -> ReconnectOptions {
+        let summaries = summaries.clone();
+        ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(10); 2])
+            .with_on_give_up_callback(move |summary| {
+                summaries.lock().unwrap().push((
+                    summary.attempts,
+                    summary.last_error.map(|err| err.to_string()),
+                ));
+            })
+    }
+
+    #[tokio::test]
+    async fn should_summarize_the_exhausted_reconnects() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, false, false])),
+            poll_read_results: Arc::new(Mutex::new(vec![(
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "fatal",
+                ))),
+                vec![],
+            )])),
+            connect_error_kinds: Arc::new(Mutex::new(vec![
+                ErrorKind::ConnectionRefused,
+                ErrorKind::TimedOut,
+            ])),
+            ..DummyCtor::default()
+        };
+
+        let summaries = Summaries::default();
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options(&summaries))
+            .await
+            .unwrap();
+        assert!(dummy.next().await.is_none());
+        assert!(dummy.is_dead());
+        assert_eq!(
+            *summaries.lock().unwrap(),
+            vec![(2, Some(String::from("So unfortunate")))]
+        );
+    }
+
+    #[tokio::test]
+    async fn should_summarize_the_failed_initial_connect() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![false])),
+            ..DummyCtor::default()
+        };
+
+        let summaries = Summaries::default();
+        let result = ReconnectDummy::connect_with_options(ctor, options(&summaries)).await;
+        assert!(result.is_err());
+        assert_eq!(
+            *summaries.lock().unwrap(),
+            vec![(
+                1,
+                Some(String::from("initial connection failed: So unfortunate"))
+            )]
+        );
+    }
+}
+
+#[cfg(test)]
+mod persistent_schedule {
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_share_the_retries_across_episodes() {
+        let aborted = || {
+            (
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "fatal",
+                ))),
+                vec![],
+            )
+        };
+        let connect_outcomes = Arc::new(Mutex::new(vec![true, true, true]));
+        let ctor = DummyCtor {
+            connect_outcomes: connect_outcomes.clone(),
+            poll_read_results: Arc::new(Mutex::new(vec![aborted(), aborted(), aborted()])),
+            ..DummyCtor::default()
+        };
+
+        let generated = Arc::new(AtomicU8::new(0));
+        let generated_clone = generated.clone();
+        let options = ReconnectOptions::new()
+            .with_retries_generator(move || {
+                generated_clone.fetch_add(1, Ordering::Relaxed);
+                vec![Duration::from_millis(10); 2]
+            })
+            .with_persistent_schedule(true);
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        assert_eq!(generated.load(Ordering::Relaxed), 0);
+
+        assert!(dummy.next().await.is_none());
+        assert!(dummy.is_dead());
+        assert_eq!(dummy.stats().reconnects, 2);
+        assert_eq!(dummy.stats().disconnects, 3);
+        assert_eq!(generated.load(Ordering::Relaxed), 1);
+        assert!(connect_outcomes.lock().unwrap().is_empty());
+    }
+}
+
+#[cfg(test)]
+mod attempts {
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_yield_the_outcome_of_each_reconnect_attempt() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, false, true])),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (
+                    Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "fatal",
+                    ))),
+                    vec![],
+                ),
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+            ])),
+            ..DummyCtor::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(10); 2]);
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        let attempts = dummy.attempts();
+
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+        drop(dummy);
+        let attempts = attempts
+            .map(|outcome| outcome.map_err(|err| err.to_string()))
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(attempts, vec![Err(String::from("So unfortunate")), Ok(())]);
+    }
+}
+
+#[cfg(test)]
+mod migrate {
+    use futures::{SinkExt, StreamExt};
+
+    use stream_reconnect::{ConnectionStatus, ReconnectError};
+
+    use super::*;
+
+    fn ctor(connect_outcomes: Vec<bool>, read: &[u8]) -> DummyCtor {
+        DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(connect_outcomes)),
+            poll_read_results: Arc::new(Mutex::new(vec![(Poll::Ready(Ok(())), read.to_vec())])),
+            ..DummyCtor::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn should_switch_over_to_the_new_connection() {
+        let old_ctor = ctor(vec![true], b"a");
+        let old_sent = old_ctor.sent_items.clone();
+        let new_ctor = ctor(vec![true], b"b");
+        let new_sent = new_ctor.sent_items.clone();
+
+        let mut dummy = ReconnectDummy::connect(old_ctor).await.unwrap();
+        dummy.send(b"x".to_vec()).await.unwrap();
+        dummy.migrate::<Vec<u8>>(new_ctor).await.unwrap();
+
+        assert_eq!(dummy.next().await.unwrap(), b"b".to_vec());
+        dummy.send(b"y".to_vec()).await.unwrap();
+        assert_eq!(*old_sent.lock().unwrap(), vec![b"x".to_vec()]);
+        assert_eq!(*new_sent.lock().unwrap(), vec![b"y".to_vec()]);
+        assert_eq!(dummy.stats().disconnects, 0);
+    }
+
+    #[tokio::test]
+    async fn should_keep_the_old_connection_if_the_new_one_fails() {
+        let mut dummy = ReconnectDummy::connect(ctor(vec![true], b"a"))
+            .await
+            .unwrap();
+        let result = dummy.migrate::<Vec<u8>>(ctor(vec![false], b"b")).await;
+
+        assert!(matches!(result, Err(ReconnectError::Underlying(_))));
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+    }
+
+    #[tokio::test]
+    async fn should_stay_usable_if_cancelled_while_flushing_the_old_connection() {
+        let old_ctor = DummyCtor {
+            stuck_flushes: Arc::new(Mutex::new(1)),
+            ..ctor(vec![true], b"a")
+        };
+        let new_ctor = ctor(vec![true], b"b");
+        let new_sent = new_ctor.sent_items.clone();
+
+        let mut dummy = ReconnectDummy::connect(old_ctor).await.unwrap();
+        let migrate = dummy.migrate::<Vec<u8>>(new_ctor);
+        assert!(tokio::time::timeout(Duration::from_millis(50), migrate)
+            .await
+            .is_err());
+
+        assert_eq!(dummy.status(), ConnectionStatus::Connected);
+        assert_eq!(dummy.next().await.unwrap(), b"b".to_vec());
+        dummy.send(b"y".to_vec()).await.unwrap();
+        assert_eq!(*new_sent.lock().unwrap(), vec![b"y".to_vec()]);
+    }
+}
+
+#[cfg(test)]
+mod near_exhaustion {
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    fn dummy_ctor() -> DummyCtor {
+        DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, false, false, false])),
+            poll_read_results: Arc::new(Mutex::new(vec![(
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "fatal",
+                ))),
+                vec![],
+            )])),
+            ..DummyCtor::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn should_warn_once_about_the_last_delays() {
+        let remaining: Arc<Mutex<Vec<usize>>> = Arc::default();
+        let remaining_clone = remaining.clone();
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| vec![Duration::from_millis(10); 3])
+            .with_on_near_exhaustion_callback(1, move |left| {
+                remaining_clone.lock().unwrap().push(left)
+            });
+        let mut dummy = ReconnectDummy::connect_with_options(dummy_ctor(), options)
+            .await
+            .unwrap();
+
+        assert!(dummy.next().await.is_none());
+        assert!(dummy.is_dead());
+        assert_eq!(dummy.stats().failed_attempts, 3);
+        assert_eq!(*remaining.lock().unwrap(), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn should_warn_about_the_last_delay_of_an_unsized_iterator() {
+        let remaining: Arc<Mutex<Vec<usize>>> = Arc::default();
+        let remaining_clone = remaining.clone();
+        let options = ReconnectOptions::new()
+            .with_retries_generator(|| {
+                vec![Duration::from_millis(10); 3]
+                    .into_iter()
+                    .filter(|_| true)
+            })
+            .with_on_near_exhaustion_callback(1, move |left| {
+                remaining_clone.lock().unwrap().push(left)
+            });
+        let mut dummy = ReconnectDummy::connect_with_options(dummy_ctor(), options)
+            .await
+            .unwrap();
+
+        assert!(dummy.next().await.is_none());
+        assert_eq!(dummy.stats().failed_attempts, 3);
+        assert_eq!(*remaining.lock().unwrap(), vec![0]);
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod options_watch {
+    use futures::stream::StreamExt;
+    use tokio::sync::watch;
+
+    use super::*;
+
+    fn options(retries: usize) -> ReconnectOptions {
+        ReconnectOptions::new()
+            .with_retries_generator(move || vec![Duration::from_millis(10); retries])
+    }
+
+    #[tokio::test]
+    async fn should_apply_the_new_options_on_the_next_episode() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, false, false, true])),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (
+                    Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "fatal",
+                    ))),
+                    vec![],
+                ),
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+            ])),
+            ..DummyCtor::default()
+        };
+
+        let (tx, rx) = watch::channel(options(1));
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options(1))
+            .await
+            .unwrap();
+        dummy.watch_options(rx);
+        tx.send(options(3)).unwrap();
+        drop(tx);
+
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+        assert_eq!(dummy.stats().failed_attempts, 2);
+    }
+}
+
+#[cfg(test)]
+mod clean_eof {
+    use futures::stream::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_end_without_reconnecting() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, true])),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+                (Poll::Ready(Err(io::Error::other("eof"))), vec![]),
+            ])),
+            clean_eof: true,
+            ..DummyCtor::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(10)]);
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+
+        assert_eq!(dummy.next().await.unwrap(), b"a".to_vec());
+        assert!(dummy.next().await.is_none());
+        assert_eq!(dummy.stats().disconnects, 0);
+        assert!(!dummy.reconnection_enabled());
+    }
+}
+
+#[cfg(test)]
+mod initial_connect_send_policy {
+    use futures::SinkExt;
+
+    use stream_reconnect::{InitialConnectSendPolicy, ReconnectError};
+
+    use super::*;
+
+    async fn lazy_dummy(policy: InitialConnectSendPolicy) -> (ReconnectDummy, SentItems) {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, true])),
+            // the connect of the lazy stream takes a while
+            connect_delays: Arc::new(Mutex::new(vec![Duration::ZERO, Duration::from_millis(50)])),
+            ..DummyCtor::default()
+        };
+        let sent_items = ctor.sent_items.clone();
+        let options = ReconnectOptions::new().with_initial_connect_send_policy(policy);
+        let dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        (dummy.reconfigure_clone(), sent_items)
+    }
+
+    #[tokio::test]
+    async fn should_buffer_until_connected() {
+        let (mut lazy, sent_items) = lazy_dummy(InitialConnectSendPolicy::Buffer).await;
+
+        Pin::new(&mut lazy).start_send(b"a".to_vec()).unwrap();
+        assert!(sent_items.lock().unwrap().is_empty());
+        lazy.send(b"b".to_vec()).await.unwrap();
+        assert_eq!(
+            *sent_items.lock().unwrap(),
+            vec![b"a".to_vec(), b"b".to_vec()]
+        );
+    }
+
+    #[tokio::test]
+    async fn should_fail_until_connected() {
+        let (mut lazy, sent_items) = lazy_dummy(InitialConnectSendPolicy::Error).await;
+
+        let result = lazy.send(b"a".to_vec()).await;
+        assert!(matches!(result, Err(ReconnectError::Disconnected)));
+        lazy.wait_connected().await.unwrap();
+        lazy.send(b"b".to_vec()).await.unwrap();
+        assert_eq!(*sent_items.lock().unwrap(), vec![b"b".to_vec()]);
+    }
+
+    #[tokio::test]
+    async fn should_block_until_connected() {
+        let (mut lazy, sent_items) = lazy_dummy(InitialConnectSendPolicy::Block).await;
+
+        let result = Pin::new(&mut lazy).start_send(b"a".to_vec());
+        assert!(matches!(result, Err(ReconnectError::Disconnected)));
+        lazy.send(b"b".to_vec()).await.unwrap();
+        assert_eq!(*sent_items.lock().unwrap(), vec![b"b".to_vec()]);
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod changed {
+    use futures::stream::StreamExt;
+
+    use stream_reconnect::ConnectionStatus;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn should_resolve_on_the_next_status() {
+        let ctor = DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, true])),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (
+                    Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "fatal",
+                    ))),
+                    vec![],
+                ),
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+            ])),
+            ..DummyCtor::default()
+        };
+
+        let options =
+            ReconnectOptions::new().with_retries_generator(|| vec![Duration::from_millis(10)]);
+        let mut dummy = ReconnectDummy::connect_with_options(ctor, options)
+            .await
+            .unwrap();
+        let changed = dummy.changed();
+
+        let (item, status) = tokio::join!(dummy.next(), changed);
+        assert_eq!(item.unwrap(), b"a".to_vec());
+        assert_eq!(status, ConnectionStatus::Reconnecting { attempt: 1 });
+
+        let changed = dummy.changed();
+        drop(dummy);
+        assert_eq!(changed.await, ConnectionStatus::Connected);
+    }
+}
+
+#[cfg(test)]
+mod reconnect_limiter {
+    use std::time::Instant;
+
+    use futures::stream::StreamExt;
+
+    use stream_reconnect::ReconnectLimiter;
+
+    use super::*;
+
+    fn ctor() -> DummyCtor {
+        DummyCtor {
+            connect_outcomes: Arc::new(Mutex::new(vec![true, true])),
+            poll_read_results: Arc::new(Mutex::new(vec![
+                (
+                    Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "fatal",
+                    ))),
+                    vec![],
+                ),
+                (Poll::Ready(Ok(())), b"a".to_vec()),
+            ])),
+            ..DummyCtor::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn should_share_the_budget_between_streams() {
+        let limiter = Arc::new(ReconnectLimiter::new(1, Duration::from_millis(300)));
+        let options = || {
+            ReconnectOptions::new()
+                .with_retries_generator(|| vec![Duration::ZERO])
+                .with_reconnect_limiter(limiter.clone())
+        };
+        let mut first = ReconnectDummy::connect_with_options(ctor(), options())
+            .await
+            .unwrap();
+        let mut second = ReconnectDummy::connect_with_options(ctor(), options())
+            .await
+            .unwrap();
+
+        let started_at = Instant::now();
+        let (first_item, second_item) = tokio::join!(first.next(), second.next());
+        assert_eq!(first_item.unwrap(), b"a".to_vec());
+        assert_eq!(second_item.unwrap(), b"a".to_vec());
+        assert!(started_at.elapsed() >= Duration::from_millis(250));
+    }
+
+    #[tokio::test]
+    async fn should_take_a_token_for_each_migration() {
+        let limiter = Arc::new(ReconnectLimiter::new(1, Duration::from_millis(300)));
+        let options = ReconnectOptions::new().with_reconnect_limiter(limiter);
+        let mut dummy = ReconnectDummy::connect_with_options(ctor(), options)
+            .await
+            .unwrap();
+
+        let started_at = Instant::now();
+        // the initial connect took the only token
+        dummy.migrate::<Vec<u8>>(ctor()).await.unwrap();
+        assert!(started_at.elapsed() >= Duration::from_millis(250));
+        dummy.migrate::<Vec<u8>>(ctor()).await.unwrap();
+        assert!(started_at.elapsed() >= Duration::from_millis(550));
+    }
+
+    #[tokio::test]
+    async fn should_take_a_token_for_the_first_attempt_of_the_initial_connect() {
+        let limiter = Arc::new(ReconnectLimiter::new(1, Duration::from_millis(300)));
+        let options = || ReconnectOptions::new().with_reconnect_limiter(limiter.clone());
+
+        let started_at = Instant::now();
+        ReconnectDummy::connect_with_options(ctor(), options())
+            .await
+            .unwrap();
+        assert!(started_at.elapsed() < Duration::from_millis(250));
+        ReconnectDummy::connect_with_options(ctor(), options())
+            .await
+            .unwrap();
+        assert!(started_at.elapsed() >= Duration::from_millis(250));
+    }
+
+    #[tokio::test]
+    async fn should_take_a_token_for_the_first_attempt_of_a_clone() {
+        let limiter = Arc::new(ReconnectLimiter::new(1, Duration::from_millis(300)));
+        let options = ReconnectOptions::new().with_reconnect_limiter(limiter);
+        let dummy = ReconnectDummy::connect_with_options(
+            DummyCtor {
+                connect_outcomes: Arc::new(Mutex::new(vec![true, true, true])),
+                ..DummyCtor::default()
+            },
+            options,
+        )
+        .await
+        .unwrap();
+
+        let started_at = Instant::now();
+        let mut first = dummy.reconfigure_clone();
+        let mut second = dummy.reconfigure_clone();
+        let (first, second) = tokio::join!(first.wait_connected(), second.wait_connected());
+        first.unwrap();
+        second.unwrap();
+        assert!(started_at.elapsed() >= Duration::from_millis(250));
+    }
+}